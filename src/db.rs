@@ -1,11 +1,32 @@
-use crate::task::{Task, TaskList, TaskTime, WorkDate};
+use crate::task::{BreakCategory, Task, TaskList, TaskTime, WorkDate, DEFAULT_BREAK_CATEGORY};
 use anyhow::{anyhow, Result};
 use getset::Getters;
-use rusqlite::{params, Connection, OpenFlags};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension, Row};
+use std::collections::HashMap;
 use std::env;
 use std::fmt;
 use std::path::PathBuf;
 
+/// Steps to migrate tables created by an older version, the n-th of which migrates
+/// the schema of version n to version n + 1.
+const MIGRATIONS: &[fn(&Connection) -> Result<()>] = &[
+    migrate_taskname_ids,
+    migrate_archived_tasknames,
+    migrate_projects,
+    migrate_tags,
+    migrate_notes,
+    migrate_fts,
+    migrate_external_refs,
+    migrate_repos,
+    migrate_dir_mappings,
+    migrate_paused_task,
+    migrate_task_stack,
+    migrate_break_categories,
+];
+
+/// Version of the database schema, stored in `user_version` of the database.
+const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
 /// A Struct represents a database.
 #[derive(Getters)]
 pub struct Database {
@@ -22,14 +43,18 @@ impl Database {
         }
     }
 
+    /// Create a `Database` from a connection, enabling foreign key constraints.
+    fn new(conn: Connection, location: DatabaseLocation) -> Result<Database> {
+        conn.execute_batch("PRAGMA foreign_keys = ON")?;
+
+        Ok(Database { conn, location })
+    }
+
     /// Connect to the database.
     fn connect(path: &PathBuf, flags: OpenFlags) -> Result<Database> {
         let conn = Connection::open_with_flags(path, flags)?;
 
-        Ok(Database {
-            conn,
-            location: DatabaseLocation::File(path.to_path_buf()),
-        })
+        Self::new(conn, DatabaseLocation::File(path.to_path_buf()))
     }
 
     /// Connect to the database (read/write/create mode).
@@ -42,73 +67,266 @@ impl Database {
 
     /// Connect to the database (read/write mode).
     pub fn connect_rw(path: &PathBuf) -> Result<Database> {
-        Self::connect(path, OpenFlags::SQLITE_OPEN_READ_WRITE)
+        Self::connect(path, OpenFlags::SQLITE_OPEN_READ_WRITE)?.check_schema()
     }
 
     /// Connect to the database (read only mode).
     pub fn connect_r(path: &PathBuf) -> Result<Database> {
-        Self::connect(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        Self::connect(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?.check_schema()
+    }
+
+    /// Fail if the tables are created by an older version and need to be migrated.
+    fn check_schema(self) -> Result<Database> {
+        if self.is_ready()? && self.is_outdated()? {
+            return Err(anyhow!(
+                "database schema is outdated, run `init` to migrate"
+            ));
+        }
+
+        Ok(self)
     }
 
-    /// Check whether the tables are crated.
+    /// Check whether the tables are created, by this or an older version.
     pub fn is_ready(&self) -> Result<bool> {
         let count = self.conn.query_row(
             "SELECT count(name) \
             FROM sqlite_master \
-            WHERE type = 'table' and name in ('tasks', 'tasknames', 'manager')",
+            WHERE type = 'table' \
+                and name in (\
+                    'tasks', 'tasknames', 'manager', 'projects', 'clients', 'tags', 'task_tags',\
+                    'tasks_fts', 'dir_mappings', 'task_stack', 'break_categories'\
+                )",
             [],
             |row| row.get::<_, u32>(0),
         )?;
 
-        Ok(count == 3)
+        Ok(count > 0)
+    }
+
+    /// Check whether the tables are created by an older version.
+    pub fn is_outdated(&self) -> Result<bool> {
+        Ok(self.schema_version()? < SCHEMA_VERSION)
+    }
+
+    /// Get the version of the schema, which is 0 for tables created by 0.5.0 or older.
+    fn schema_version(&self) -> Result<u32> {
+        let version = self
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get::<_, u32>(0))?;
+
+        Ok(version)
+    }
+
+    /// Migrate the tables created by an older version to the current schema, keeping their data.
+    pub fn migrate(&mut self) -> Result<()> {
+        let version = self.schema_version()?;
+        if version > SCHEMA_VERSION {
+            return Err(anyhow!(
+                "database schema is newer than this version of tasklog"
+            ));
+        }
+
+        let tx = self.conn.transaction()?;
+
+        for migration in &MIGRATIONS[version as usize..] {
+            migration(&tx)?;
+        }
+        renumber_tasknames(&tx)?;
+
+        tx.execute_batch(&format!("PRAGMA user_version = {}", SCHEMA_VERSION))?;
+
+        tx.commit()?;
+
+        Ok(())
     }
 
     /// Create a database and initialize its tables.
     pub fn initialize(&mut self) -> Result<()> {
         let tx = self.conn.transaction()?;
 
-        &tx.execute("DROP TABLE IF EXISTS tasks", [])?;
-        &tx.execute("DROP TABLE IF EXISTS tasknames", [])?;
-        &tx.execute("DROP TABLE IF EXISTS manager", [])?;
+        tx.execute("DROP TABLE IF EXISTS manager", [])?;
+        tx.execute("DROP TABLE IF EXISTS task_stack", [])?;
+        tx.execute("DROP TABLE IF EXISTS tasks_fts", [])?;
+        tx.execute("DROP TABLE IF EXISTS task_tags", [])?;
+        tx.execute("DROP TABLE IF EXISTS tags", [])?;
+        tx.execute("DROP TABLE IF EXISTS tasks", [])?;
+        tx.execute("DROP TABLE IF EXISTS break_categories", [])?;
+        tx.execute("DROP TABLE IF EXISTS dir_mappings", [])?;
+        tx.execute("DROP TABLE IF EXISTS tasknames", [])?;
+        tx.execute("DROP TABLE IF EXISTS projects", [])?;
+        tx.execute("DROP TABLE IF EXISTS clients", [])?;
+
+        tx.execute(
+            "CREATE TABLE clients (\
+                id INTEGER PRIMARY KEY AUTOINCREMENT,\
+                client_name TEXT NOT NULL UNIQUE \
+            )",
+            [],
+        )?;
+
+        tx.execute(
+            "CREATE TABLE projects (\
+                id INTEGER PRIMARY KEY AUTOINCREMENT,\
+                project_name TEXT NOT NULL UNIQUE,\
+                client_id INTEGER REFERENCES clients (id) ON DELETE SET NULL \
+            )",
+            [],
+        )?;
+
+        // `seq_num` is the display order of registered names which are not archived,
+        // and names only used by task logs (e.g. break time or unregistered names) are kept
+        // unregistered.
+        tx.execute(
+            "CREATE TABLE tasknames (\
+                id INTEGER PRIMARY KEY AUTOINCREMENT,\
+                task_name TEXT NOT NULL UNIQUE,\
+                seq_num INTEGER,\
+                registered INTEGER NOT NULL DEFAULT 1,\
+                archived INTEGER NOT NULL DEFAULT 0,\
+                project_id INTEGER REFERENCES projects (id) ON DELETE SET NULL \
+            )",
+            [],
+        )?;
+
+        tx.execute(
+            "CREATE TABLE dir_mappings (\
+                id INTEGER PRIMARY KEY AUTOINCREMENT,\
+                pattern TEXT NOT NULL UNIQUE,\
+                taskname_id INTEGER NOT NULL REFERENCES tasknames (id) ON DELETE CASCADE \
+            )",
+            [],
+        )?;
+
+        // categories of non-working time, and whether each of them counts as working time
+        tx.execute(
+            "CREATE TABLE break_categories (\
+                id INTEGER PRIMARY KEY AUTOINCREMENT,\
+                category_name TEXT NOT NULL UNIQUE,\
+                counts_as_work INTEGER NOT NULL DEFAULT 0 \
+            )",
+            [],
+        )?;
 
-        &tx.execute(
+        tx.execute(
+            "INSERT INTO break_categories (category_name) \
+            VALUES (?1)",
+            params![DEFAULT_BREAK_CATEGORY],
+        )?;
+
+        // `category_id` is null for working tasks
+        tx.execute(
             "CREATE TABLE tasks (\
                 id INTEGER PRIMARY KEY AUTOINCREMENT,\
-                name TEXT,\
+                taskname_id INTEGER NOT NULL REFERENCES tasknames (id),\
                 working_date TEXT,\
                 seq_num INTEGER,\
                 start_time TEXT,\
                 end_time TEXT, \
-                is_break INTEGER \
+                category_id INTEGER REFERENCES break_categories (id),\
+                note TEXT,\
+                external_ref TEXT,\
+                repo TEXT,\
+                branch TEXT \
             )",
             [],
         )?;
 
-        &tx.execute(
-            "CREATE TABLE tasknames (\
+        tx.execute(
+            "CREATE TABLE tags (\
                 id INTEGER PRIMARY KEY AUTOINCREMENT,\
-                task_name TEXT,\
-                seq_num INTEGER \
+                tag_name TEXT NOT NULL UNIQUE \
+            )",
+            [],
+        )?;
+
+        tx.execute(
+            "CREATE TABLE task_tags (\
+                task_id INTEGER NOT NULL REFERENCES tasks (id) ON DELETE CASCADE,\
+                tag_id INTEGER NOT NULL REFERENCES tags (id) ON DELETE CASCADE,\
+                PRIMARY KEY (task_id, tag_id) \
+            )",
+            [],
+        )?;
+
+        // full-text index of task names and notes, whose rowid is the id of the task log
+        tx.execute(
+            "CREATE VIRTUAL TABLE tasks_fts USING fts5(task_name, note)",
+            [],
+        )?;
+
+        tx.execute(
+            "CREATE TRIGGER tasks_fts_insert AFTER INSERT ON tasks \
+            BEGIN \
+                INSERT INTO tasks_fts (rowid, task_name, note) \
+                SELECT new.id, task_name, new.note \
+                FROM tasknames \
+                WHERE id = new.taskname_id; \
+            END",
+            [],
+        )?;
+
+        tx.execute(
+            "CREATE TRIGGER tasks_fts_update AFTER UPDATE OF taskname_id, note ON tasks \
+            BEGIN \
+                DELETE FROM tasks_fts \
+                WHERE rowid = old.id; \
+                INSERT INTO tasks_fts (rowid, task_name, note) \
+                SELECT new.id, task_name, new.note \
+                FROM tasknames \
+                WHERE id = new.taskname_id; \
+            END",
+            [],
+        )?;
+
+        tx.execute(
+            "CREATE TRIGGER tasks_fts_delete AFTER DELETE ON tasks \
+            BEGIN \
+                DELETE FROM tasks_fts \
+                WHERE rowid = old.id; \
+            END",
+            [],
+        )?;
+
+        tx.execute(
+            "CREATE TRIGGER tasks_fts_rename AFTER UPDATE OF task_name ON tasknames \
+            BEGIN \
+                DELETE FROM tasks_fts \
+                WHERE rowid IN (SELECT id FROM tasks WHERE taskname_id = new.id); \
+                INSERT INTO tasks_fts (rowid, task_name, note) \
+                SELECT id, new.task_name, note \
+                FROM tasks \
+                WHERE taskname_id = new.id; \
+            END",
+            [],
+        )?;
+
+        // tasks suspended by interruptions, the last one is the top
+        tx.execute(
+            "CREATE TABLE task_stack (\
+                id INTEGER PRIMARY KEY AUTOINCREMENT,\
+                task_id INTEGER NOT NULL REFERENCES tasks (id) ON DELETE CASCADE \
             )",
             [],
         )?;
 
-        &tx.execute(
+        tx.execute(
             "CREATE TABLE manager (\
                 id INTEGER PRIMARY KEY AUTOINCREMENT,\
-                task_id INTEGER,\
-                task_name TEXT,\
-                start_time TEXT \
+                task_id INTEGER REFERENCES tasks (id) ON DELETE SET NULL,\
+                start_time TEXT,\
+                paused_task_id INTEGER REFERENCES tasks (id) ON DELETE SET NULL \
             )",
             [],
         )?;
 
-        &tx.execute(
+        tx.execute(
             "INSERT INTO manager (id) \
             VALUES (0)",
             [],
         )?;
 
+        tx.execute_batch(&format!("PRAGMA user_version = {}", SCHEMA_VERSION))?;
+
         tx.commit()?;
 
         Ok(())
@@ -116,85 +334,176 @@ impl Database {
 
     /// Register a task into the database to be able to select easily.
     pub fn register_taskname(&mut self, task_name: &str) -> Result<()> {
-        let exist = self.conn.query_row(
-            "SELECT id \
-            FROM tasknames \
-            WHERE task_name = ?1",
-            params![task_name],
-            |row| row.get::<_, u32>(0),
-        );
+        let exist = self
+            .conn
+            .query_row(
+                "SELECT registered \
+                FROM tasknames \
+                WHERE task_name = ?1",
+                params![task_name],
+                |row| row.get::<_, bool>(0),
+            )
+            .optional()?;
 
-        match exist {
-            Ok(_) => Err(anyhow!("task already exists")),
-            Err(_) => {
-                let tx = self.conn.transaction()?;
+        let tx = self.conn.transaction()?;
 
-                &tx.execute(
+        match exist {
+            Some(true) => return Err(anyhow!("task already exists")),
+            // a name only used by task logs
+            Some(false) => {
+                tx.execute(
+                    "UPDATE tasknames \
+                    SET registered = 1 \
+                    WHERE task_name = ?1",
+                    params![task_name],
+                )?;
+            }
+            None => {
+                tx.execute(
                     "INSERT INTO tasknames (task_name) \
                     VALUES (?1)",
                     params![task_name],
                 )?;
-                // set the sequence number ordering by `task_name`
-                &tx.execute(
-                    "UPDATE tasknames AS a \
-                    SET seq_num = n \
-                    FROM (\
-                        SELECT \
-                            id,\
-                            row_number() OVER (ORDER BY task_name) AS n \
-                        FROM tasknames \
-                    ) AS b \
-                    WHERE a.id = b.id",
-                    [],
-                )?;
-
-                tx.commit()?;
-
-                Ok(())
             }
         }
+        renumber_tasknames(&tx)?;
+
+        tx.commit()?;
+
+        Ok(())
     }
 
     /// Delete a registered task name from the database.
+    ///
+    /// The name is kept unregistered if task logs refer to it.
     pub fn unregister_taskname(&mut self, task_name: &str) -> Result<()> {
-        let exist = self.conn.query_row(
-            "SELECT id \
-            FROM tasknames \
-            WHERE task_name = ?1",
-            params![task_name],
+        let exist = self
+            .conn
+            .query_row(
+                "SELECT id \
+                FROM tasknames \
+                WHERE task_name = ?1 AND registered = 1",
+                params![task_name],
+                |row| row.get::<_, u32>(0),
+            )
+            .optional()?;
+        let id = exist.ok_or(anyhow!("task does not exist"))?;
+
+        let tx = self.conn.transaction()?;
+
+        let is_used = tx.query_row(
+            "SELECT count(id) \
+            FROM tasks \
+            WHERE taskname_id = ?1",
+            params![id],
             |row| row.get::<_, u32>(0),
-        );
+        )? > 0;
+
+        if is_used {
+            tx.execute(
+                "UPDATE tasknames \
+                SET registered = 0 \
+                WHERE id = ?1",
+                params![id],
+            )?;
+        } else {
+            tx.execute(
+                "DELETE FROM tasknames \
+                WHERE id = ?1",
+                params![id],
+            )?;
+        }
+        renumber_tasknames(&tx)?;
 
-        match exist {
-            Ok(_) => {
-                let tx = self.conn.transaction()?;
+        tx.commit()?;
 
-                &tx.execute(
-                    "DELETE FROM tasknames \
-                    WHERE task_name = ?1",
-                    params![task_name],
-                )?;
+        Ok(())
+    }
+
+    /// Rename a task name, returns `true` if it is merged into an existing name.
+    ///
+    /// Task logs follow the new name unless `keep_history` is set, in which case they keep
+    /// the old name as an unregistered one.
+    pub fn rename_taskname(&mut self, old: &str, new: &str, keep_history: bool) -> Result<bool> {
+        if old == new {
+            return Err(anyhow!("new name is the same as the old one"));
+        }
+
+        let find = |conn: &Connection, name: &str| {
+            conn.query_row(
+                "SELECT id, registered \
+                FROM tasknames \
+                WHERE task_name = ?1",
+                params![name],
+                |row| Ok((row.get::<_, u32>(0)?, row.get::<_, bool>(1)?)),
+            )
+            .optional()
+        };
+        let (old_id, old_registered) =
+            find(&self.conn, old)?.ok_or(anyhow!("task does not exist"))?;
+        let new_exist = find(&self.conn, new)?;
+
+        let tx = self.conn.transaction()?;
 
-                // set the sequence number ordering by `task_name`
-                &tx.execute(
-                    "UPDATE tasknames AS a \
-                    SET seq_num = n \
-                    FROM (\
-                        SELECT \
-                            id,\
-                            row_number() OVER (ORDER BY task_name) AS n \
-                        FROM tasknames \
-                    ) AS b \
-                    WHERE a.id = b.id",
-                    [],
+        // the name which takes over the old one
+        let new_id = match new_exist {
+            Some((id, _)) => id,
+            None if keep_history => {
+                tx.execute(
+                    "INSERT INTO tasknames (task_name, registered) \
+                    VALUES (?1, 0)",
+                    params![new],
+                )?;
+                tx.last_insert_rowid() as u32
+            }
+            None => {
+                tx.execute(
+                    "UPDATE tasknames \
+                    SET task_name = ?1 \
+                    WHERE id = ?2",
+                    params![new, old_id],
                 )?;
+                old_id
+            }
+        };
 
-                tx.commit()?;
+        if old_registered {
+            tx.execute(
+                "UPDATE tasknames \
+                SET registered = 1 \
+                WHERE id = ?1",
+                params![new_id],
+            )?;
+        }
 
-                Ok(())
+        if new_id != old_id {
+            if !keep_history {
+                tx.execute(
+                    "UPDATE tasks \
+                    SET taskname_id = ?1 \
+                    WHERE taskname_id = ?2",
+                    params![new_id, old_id],
+                )?;
             }
-            Err(_) => Err(anyhow!("task does not exist")),
+
+            // the old name remains only if task logs refer to it
+            tx.execute(
+                "UPDATE tasknames \
+                SET registered = 0 \
+                WHERE id = ?1",
+                params![old_id],
+            )?;
+            tx.execute(
+                "DELETE FROM tasknames \
+                WHERE id = ?1 AND id NOT IN (SELECT taskname_id FROM tasks)",
+                params![old_id],
+            )?;
         }
+        renumber_tasknames(&tx)?;
+
+        tx.commit()?;
+
+        Ok(new_exist.is_some())
     }
 
     /// Get a task name by a sequence number.
@@ -210,11 +519,12 @@ impl Database {
         Ok(taskname)
     }
 
-    /// Get all task names and its sequence number from the database.
+    /// Get all task names which are not archived and its sequence number from the database.
     pub fn get_registered_tasknames(&self) -> Result<Vec<(u32, String)>> {
         let mut stmt = self.conn.prepare(
             "SELECT seq_num, task_name \
             FROM tasknames \
+            WHERE registered = 1 AND archived = 0 \
             ORDER BY seq_num",
         )?;
 
@@ -232,126 +542,382 @@ impl Database {
         Ok(tuples)
     }
 
-    /// Add a task log to the database
-    pub fn add_task_entry(&mut self, task: &Task) -> Result<()> {
-        let task_name = task.name();
-        let working_date = task.working_date().to_string();
-        let start_time = task.start_time().to_string();
-        let end_time = match task.end_time() {
-            Some(time) => time.to_string(),
-            None => String::from(""),
-        };
-        let is_break = *task.is_break_time() as i32;
+    /// Get all archived task names from the database.
+    pub fn get_archived_tasknames(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT task_name \
+            FROM tasknames \
+            WHERE registered = 1 AND archived = 1 \
+            ORDER BY task_name",
+        )?;
+
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+        let mut names = Vec::new();
+        for name in rows {
+            names.push(name?);
+        }
+
+        Ok(names)
+    }
+
+    /// Archive or unarchive a registered task name.
+    ///
+    /// Archived names are hidden from the task name list but task logs keep referring to them.
+    pub fn set_taskname_archived(&mut self, task_name: &str, archived: bool) -> Result<()> {
+        let exist = self
+            .conn
+            .query_row(
+                "SELECT id, archived \
+                FROM tasknames \
+                WHERE task_name = ?1 AND registered = 1",
+                params![task_name],
+                |row| Ok((row.get::<_, u32>(0)?, row.get::<_, bool>(1)?)),
+            )
+            .optional()?;
+
+        let (id, is_archived) = exist.ok_or(anyhow!("task does not exist"))?;
+        if is_archived == archived {
+            return Err(anyhow!(if archived {
+                "task is already archived"
+            } else {
+                "task is not archived"
+            }));
+        }
 
         let tx = self.conn.transaction()?;
 
-        &tx.execute(
-            "INSERT INTO tasks (name, working_date, start_time, end_time, is_break) \
-            VALUES (?1, ?2, ?3, ?4, ?5)",
-            params![task_name, working_date, start_time, end_time, is_break],
+        tx.execute(
+            "UPDATE tasknames \
+            SET archived = ?1 \
+            WHERE id = ?2",
+            params![archived, id],
         )?;
+        renumber_tasknames(&tx)?;
 
-        let task_id = tx.query_row(
-            "SELECT max(id) \
-            FROM tasks",
-            [],
-            |row| Ok(row.get_unwrap::<_, u32>(0)),
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Add a project, or update the client of an existing project.
+    pub fn add_project(&mut self, project: &str, client: Option<&str>) -> Result<()> {
+        let tx = self.conn.transaction()?;
+
+        let client_id = match client {
+            Some(name) => {
+                tx.execute(
+                    "INSERT OR IGNORE INTO clients (client_name) \
+                    VALUES (?1)",
+                    params![name],
+                )?;
+                Some(tx.query_row(
+                    "SELECT id \
+                    FROM clients \
+                    WHERE client_name = ?1",
+                    params![name],
+                    |row| row.get::<_, u32>(0),
+                )?)
+            }
+            None => None,
+        };
+
+        tx.execute(
+            "INSERT INTO projects (project_name, client_id) \
+            VALUES (?1, ?2) \
+            ON CONFLICT (project_name) DO UPDATE SET client_id = excluded.client_id",
+            params![project, client_id],
         )?;
 
-        &tx.execute(
-            "UPDATE tasks AS a \
-            SET seq_num = n \
-            FROM (\
-                SELECT \
-                    id, \
-                    row_number() OVER (ORDER BY start_time) AS n \
-                FROM tasks
-                WHERE working_date = ?1\
-            ) AS b \
-            WHERE a.id = b.id",
-            params![working_date],
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Get all projects and their clients.
+    pub fn get_projects(&self) -> Result<Vec<(String, Option<String>)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT project_name, client_name \
+            FROM projects \
+            LEFT JOIN clients ON projects.client_id = clients.id \
+            ORDER BY project_name",
         )?;
 
-        &tx.execute(
-            "UPDATE manager \
-            SET \
-                task_id = ?1, \
-                task_name = ?2, \
-                start_time = ?3 \
-            WHERE id = 0",
-            params![task_id, task_name, start_time],
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        })?;
+
+        let mut tuples = Vec::new();
+        for tuple in rows {
+            tuples.push(tuple?);
+        }
+
+        Ok(tuples)
+    }
+
+    /// Assign a registered task name to a project, or unassign it if `project` is `None`.
+    pub fn assign_project(&mut self, task_name: &str, project: Option<&str>) -> Result<()> {
+        let project_id = match project {
+            Some(name) => Some(
+                self.conn
+                    .query_row(
+                        "SELECT id \
+                        FROM projects \
+                        WHERE project_name = ?1",
+                        params![name],
+                        |row| row.get::<_, u32>(0),
+                    )
+                    .optional()?
+                    .ok_or(anyhow!("project does not exist"))?,
+            ),
+            None => None,
+        };
+
+        let count = self.conn.execute(
+            "UPDATE tasknames \
+            SET project_id = ?1 \
+            WHERE task_name = ?2 AND registered = 1",
+            params![project_id, task_name],
         )?;
 
-        tx.commit()?;
+        if count == 0 {
+            Err(anyhow!("task does not exist"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Get the project and the client of each task name.
+    pub fn get_taskname_projects(&self) -> Result<ProjectMap> {
+        let mut stmt = self.conn.prepare(
+            "SELECT task_name, project_name, client_name \
+            FROM tasknames \
+            LEFT JOIN projects ON tasknames.project_id = projects.id \
+            LEFT JOIN clients ON projects.client_id = clients.id",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                (
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                ),
+            ))
+        })?;
+
+        let mut map = HashMap::new();
+        for pair in rows {
+            let (name, project_client) = pair?;
+            map.insert(name, project_client);
+        }
+
+        Ok(map)
+    }
+
+    /// Map directories matching a glob pattern to a registered task name, or change the task
+    /// name of an existing pattern.
+    pub fn add_dir_mapping(&mut self, pattern: &str, task_name: &str) -> Result<()> {
+        let taskname_id = self
+            .conn
+            .query_row(
+                "SELECT id \
+                FROM tasknames \
+                WHERE task_name = ?1 AND registered = 1",
+                params![task_name],
+                |row| row.get::<_, u32>(0),
+            )
+            .optional()?
+            .ok_or(anyhow!("task does not exist"))?;
+
+        self.conn.execute(
+            "INSERT INTO dir_mappings (pattern, taskname_id) \
+            VALUES (?1, ?2) \
+            ON CONFLICT (pattern) DO UPDATE SET taskname_id = excluded.taskname_id",
+            params![pattern, taskname_id],
+        )?;
 
         Ok(())
     }
 
-    /// Get a task from the database by id.
-    pub fn get_task(&self, id: u32) -> Result<Task> {
-        let task: Task = self.conn.query_row(
-            "SELECT id, name, start_time, end_time, is_break \
-            FROM tasks \
-            WHERE id = ?1",
-            params![id],
-            |row| {
-                let id = row.get::<_, u32>(0)?;
-                let name = row.get_unwrap::<_, String>(1);
-                let start_time =
-                    TaskTime::parse_from_str_iso8601(&row.get_unwrap::<_, String>(2)).unwrap();
-                let end_time = {
-                    match TaskTime::parse_from_str_iso8601(&row.get_unwrap::<_, String>(3)) {
-                        Ok(t) => Some(t),
-                        Err(_) => None,
-                    }
-                };
-                let is_break_time = row.get_unwrap::<_, bool>(4);
-
-                Ok(Task::new(
-                    Some(id),
-                    name,
-                    start_time,
-                    end_time,
-                    is_break_time,
-                ))
-            },
+    /// Remove a mapping of directories.
+    pub fn remove_dir_mapping(&mut self, pattern: &str) -> Result<()> {
+        let count = self.conn.execute(
+            "DELETE FROM dir_mappings \
+            WHERE pattern = ?1",
+            params![pattern],
         )?;
 
-        Ok(task)
+        if count == 0 {
+            Err(anyhow!("mapping does not exist"))
+        } else {
+            Ok(())
+        }
     }
 
-    /// Get the current task id, which is recored on `manager` table, from the database.
-    pub fn get_current_task_id(&self) -> Result<Option<u32>> {
-        let id_or_null = self.conn.query_row(
-            "SELECT task_id \
-            FROM manager",
-            [],
-            |row| row.get::<_, Option<u32>>(0),
+    /// Get all mappings of directory patterns to task names which are registered and not
+    /// archived.
+    pub fn get_dir_mappings(&self) -> Result<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT pattern, task_name \
+            FROM dir_mappings \
+            JOIN tasknames ON dir_mappings.taskname_id = tasknames.id \
+            WHERE registered = 1 AND archived = 0 \
+            ORDER BY pattern",
         )?;
 
-        Ok(id_or_null)
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut tuples = Vec::new();
+        for tuple in rows {
+            tuples.push(tuple?);
+        }
+
+        Ok(tuples)
     }
 
-    /// Get a task id from the database by specifying the task list number and date.
-    pub fn get_task_id_by_seqnum(&self, seq_num: u32, working_date: WorkDate) -> Result<u32> {
-        let id = self.conn.query_row(
-            "SELECT id \
-            FROM tasks \
-            WHERE seq_num = ?1 AND working_date = ?2",
-            params![seq_num, working_date.to_string()],
-            |row| row.get::<_, u32>(0),
+    /// Add a break category, or update whether an existing category counts as working time.
+    pub fn add_break_category(&mut self, name: &str, counts_as_work: bool) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO break_categories (category_name, counts_as_work) \
+            VALUES (?1, ?2) \
+            ON CONFLICT (category_name) DO UPDATE SET counts_as_work = excluded.counts_as_work",
+            params![name, counts_as_work],
         )?;
 
-        Ok(id)
+        Ok(())
     }
 
-    /// Get all task logs from the database, retruns vec of (sequence number, task) pairs
-    pub fn get_tasks(&self, all: bool, working_date: Option<WorkDate>) -> Result<TaskList> {
-        let sql = format!(
-            "SELECT seq_num, id, name, start_time, end_time, is_break \
+    /// Get all break categories.
+    pub fn get_break_categories(&self) -> Result<Vec<BreakCategory>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT category_name, counts_as_work \
+            FROM break_categories \
+            ORDER BY category_name",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(BreakCategory::new(
+                row.get::<_, String>(0)?,
+                row.get::<_, bool>(1)?,
+            ))
+        })?;
+
+        let mut categories = Vec::new();
+        for category in rows {
+            categories.push(category?);
+        }
+
+        Ok(categories)
+    }
+
+    /// Get a break category by its name.
+    pub fn get_break_category(&self, name: &str) -> Result<Option<BreakCategory>> {
+        let category = self
+            .conn
+            .query_row(
+                "SELECT category_name, counts_as_work \
+                FROM break_categories \
+                WHERE category_name = ?1",
+                params![name],
+                |row| {
+                    Ok(BreakCategory::new(
+                        row.get::<_, String>(0)?,
+                        row.get::<_, bool>(1)?,
+                    ))
+                },
+            )
+            .optional()?;
+
+        Ok(category)
+    }
+
+    /// Add a task log to the database
+    pub fn add_task_entry(&mut self, task: &Task) -> Result<()> {
+        let tx = self.conn.transaction()?;
+
+        let task_id = insert_task(&tx, task)?;
+        renumber_tasks(&tx, &task.working_date().to_string())?;
+
+        tx.execute(
+            "UPDATE manager \
+            SET \
+                task_id = ?1, \
+                start_time = ?2 \
+            WHERE id = 0",
+            params![task_id, task.start_time().to_string()],
+        )?;
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Add a task log to the database without changing the current task.
+    pub fn insert_task_entry(&mut self, task: &Task) -> Result<()> {
+        let tx = self.conn.transaction()?;
+
+        insert_task(&tx, task)?;
+        renumber_tasks(&tx, &task.working_date().to_string())?;
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Get a task from the database by id.
+    pub fn get_task(&self, id: u32) -> Result<Task> {
+        let sql = format!(
+            "SELECT {} \
+            FROM tasks \
+            JOIN tasknames ON tasks.taskname_id = tasknames.id \
+            WHERE tasks.id = ?1",
+            task_columns()
+        );
+        let task: Task = self
+            .conn
+            .query_row(&sql, params![id], |row| task_from_row(row, 0))?;
+
+        Ok(task)
+    }
+
+    /// Get the current task id, which is recored on `manager` table, from the database.
+    pub fn get_current_task_id(&self) -> Result<Option<u32>> {
+        let id_or_null = self.conn.query_row(
+            "SELECT task_id \
+            FROM manager",
+            [],
+            |row| row.get::<_, Option<u32>>(0),
+        )?;
+
+        Ok(id_or_null)
+    }
+
+    /// Get a task id from the database by specifying the task list number and date.
+    pub fn get_task_id_by_seqnum(&self, seq_num: u32, working_date: WorkDate) -> Result<u32> {
+        let id = self.conn.query_row(
+            "SELECT id \
             FROM tasks \
+            WHERE seq_num = ?1 AND working_date = ?2",
+            params![seq_num, working_date.to_string()],
+            |row| row.get::<_, u32>(0),
+        )?;
+
+        Ok(id)
+    }
+
+    /// Get all task logs from the database, retruns vec of (sequence number, task) pairs
+    #[allow(clippy::to_string_in_format_args)]
+    pub fn get_tasks(&self, all: bool, working_date: Option<WorkDate>) -> Result<TaskList> {
+        let sql = format!(
+            "SELECT tasks.seq_num, {} \
+            FROM tasks \
+            JOIN tasknames ON tasks.taskname_id = tasknames.id \
             {} \
-            ORDER BY working_date, seq_num",
+            ORDER BY working_date, tasks.seq_num",
+            task_columns(),
             if all {
                 String::from("")
             } else {
@@ -362,250 +928,1807 @@ impl Database {
             }
         );
 
-        let mut stmt = self.conn.prepare(&sql)?;
-        let rows = stmt.query_map([], |row| {
-            let seq_num = row.get_unwrap::<_, u32>(0);
-            let id = row.get_unwrap::<_, u32>(1);
-            let name = row.get_unwrap::<_, String>(2);
-            let start_time =
-                TaskTime::parse_from_str_iso8601(&row.get_unwrap::<_, String>(3)).unwrap();
-            let end_time = {
-                match TaskTime::parse_from_str_iso8601(&row.get_unwrap::<_, String>(4)) {
-                    Ok(t) => Some(t),
-                    Err(_) => None,
-                }
-            };
-            let is_break_time = row.get_unwrap::<_, bool>(5);
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], task_with_seqnum_from_row)?;
+
+        let mut task_list = Vec::new();
+        for tuple in rows {
+            task_list.push(tuple?);
+        }
+
+        Ok(TaskList::new(task_list))
+    }
+
+    /// Get task logs whose working dates are in the range from `first` to `last` (inclusive).
+    pub fn get_tasks_between(&self, first: WorkDate, last: WorkDate) -> Result<TaskList> {
+        let sql = format!(
+            "SELECT tasks.seq_num, {} \
+            FROM tasks \
+            JOIN tasknames ON tasks.taskname_id = tasknames.id \
+            WHERE working_date BETWEEN ?1 AND ?2 \
+            ORDER BY working_date, tasks.seq_num",
+            task_columns()
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(
+            params![first.to_string(), last.to_string()],
+            task_with_seqnum_from_row,
+        )?;
+
+        let mut task_list = Vec::new();
+        for tuple in rows {
+            task_list.push(tuple?);
+        }
+
+        Ok(TaskList::new(task_list))
+    }
+
+    /// Search task logs whose names or notes match the query, from the most recent one.
+    ///
+    /// Matched words in the name and the snippet of the note are enclosed in `HIGHLIGHT_START`
+    /// and `HIGHLIGHT_END`.
+    pub fn search_tasks(&self, query: &str, limit: u32) -> Result<Vec<SearchHit>> {
+        let sql = format!(
+            "SELECT {}, \
+                highlight(tasks_fts, 0, ?2, ?3), \
+                snippet(tasks_fts, 1, ?2, ?3, '...', 10) \
+            FROM tasks_fts \
+            JOIN tasks ON tasks_fts.rowid = tasks.id \
+            JOIN tasknames ON tasks.taskname_id = tasknames.id \
+            WHERE tasks_fts MATCH ?1 \
+            ORDER BY start_time DESC \
+            LIMIT ?4",
+            task_columns()
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(
+            params![
+                build_fts_query(query),
+                HIGHLIGHT_START,
+                HIGHLIGHT_END,
+                limit
+            ],
+            |row| {
+                Ok(SearchHit {
+                    task: task_from_row(row, 0)?,
+                    name: row.get_unwrap::<_, String>(TASK_COLUMN_COUNT),
+                    note: row
+                        .get_unwrap::<_, Option<String>>(TASK_COLUMN_COUNT + 1)
+                        .filter(|snippet| !snippet.is_empty()),
+                })
+            },
+        )?;
+
+        let mut hits = Vec::new();
+        for hit in rows {
+            hits.push(hit?);
+        }
+
+        Ok(hits)
+    }
+
+    /// Get distinct names of working tasks which are not archived, from the most recently
+    /// started one.
+    pub fn get_recent_tasknames(&self, limit: u32) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT task_name \
+            FROM tasks \
+            JOIN tasknames ON tasks.taskname_id = tasknames.id \
+            WHERE category_id IS NULL AND archived = 0 \
+            GROUP BY tasknames.id \
+            ORDER BY max(start_time) DESC \
+            LIMIT ?1",
+        )?;
+
+        let rows = stmt.query_map(params![limit], |row| row.get::<_, String>(0))?;
+
+        let mut names = Vec::new();
+        for name in rows {
+            names.push(name?);
+        }
+
+        Ok(names)
+    }
+
+    /// Update a task log in the database.
+    pub fn update_task(&self, id: u32, updated_task: &Task) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+
+        write_task(&tx, id, updated_task)?;
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Split a task log into two adjacent ones, the first of which replaces the task log.
+    ///
+    /// The second one becomes the current task if the task log is running.
+    pub fn split_task(&mut self, id: u32, first: &Task, second: &Task) -> Result<()> {
+        let tx = self.conn.transaction()?;
+
+        write_task(&tx, id, first)?;
+        let second_id = insert_task(&tx, second)?;
+        renumber_tasks(&tx, &first.working_date().to_string())?;
+
+        tx.execute(
+            "UPDATE manager \
+            SET \
+                task_id = ?1, \
+                start_time = ?2 \
+            WHERE id = 0 AND task_id = ?3",
+            params![second_id, second.start_time().to_string(), id],
+        )?;
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Delete a task.
+    pub fn delete_task(&mut self, id: u32) -> Result<()> {
+        let working_date = self.get_task(id)?.working_date().to_string();
+
+        let tx = self.conn.transaction()?;
+
+        tx.execute(
+            "DELETE FROM tasks
+            WHERE id = ?1",
+            params![id],
+        )?;
+
+        renumber_tasks(&tx, &working_date)?;
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Replace a task log with a merged one and delete the task logs merged into it.
+    ///
+    /// References to the deleted task logs from the manager and the stack of suspended tasks
+    /// are moved to the merged one.
+    pub fn merge_tasks(&mut self, id: u32, merged: &Task, merged_ids: &[u32]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+
+        for merged_id in merged_ids {
+            tx.execute(
+                "UPDATE manager \
+                SET \
+                    task_id = ?1, \
+                    start_time = ?2 \
+                WHERE id = 0 AND task_id = ?3",
+                params![id, merged.start_time().to_string(), merged_id],
+            )?;
+            tx.execute(
+                "UPDATE manager \
+                SET paused_task_id = ?1 \
+                WHERE id = 0 AND paused_task_id = ?2",
+                params![id, merged_id],
+            )?;
+            tx.execute(
+                "UPDATE task_stack \
+                SET task_id = ?1 \
+                WHERE task_id = ?2",
+                params![id, merged_id],
+            )?;
+            tx.execute(
+                "DELETE FROM tasks \
+                WHERE id = ?1",
+                params![merged_id],
+            )?;
+        }
+
+        write_task(&tx, id, merged)?;
+        renumber_tasks(&tx, &merged.working_date().to_string())?;
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Reopen an ended task log by clearing its end time, and make it the current task.
+    pub fn reopen_task(&mut self, id: u32) -> Result<()> {
+        let tx = self.conn.transaction()?;
+
+        tx.execute(
+            "UPDATE tasks \
+            SET end_time = '' \
+            WHERE id = ?1",
+            params![id],
+        )?;
+        tx.execute(
+            "UPDATE manager \
+            SET \
+                task_id = tasks.id, \
+                start_time = tasks.start_time \
+            FROM tasks \
+            WHERE manager.id = 0 AND tasks.id = ?1",
+            params![id],
+        )?;
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Reset manager entry.
+    pub fn reset_manager(&self) -> Result<()> {
+        self.conn.execute(
+            "UPDATE manager \
+            SET \
+                task_id = NULL, \
+                start_time = NULL \
+            WHERE id = 0",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// Push a suspended task onto the stack.
+    pub fn push_task_stack(&self, task_id: u32) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO task_stack (task_id) \
+            VALUES (?1)",
+            params![task_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Pop the task on the top of the stack.
+    pub fn pop_task_stack(&mut self) -> Result<Option<u32>> {
+        let tx = self.conn.transaction()?;
+
+        let top = tx
+            .query_row(
+                "SELECT id, task_id \
+                FROM task_stack \
+                ORDER BY id DESC \
+                LIMIT 1",
+                [],
+                |row| Ok((row.get::<_, u32>(0)?, row.get::<_, u32>(1)?)),
+            )
+            .optional()?;
+
+        if let Some((id, _)) = top {
+            tx.execute(
+                "DELETE FROM task_stack \
+                WHERE id = ?1",
+                params![id],
+            )?;
+        }
+
+        tx.commit()?;
+
+        Ok(top.map(|(_, task_id)| task_id))
+    }
+
+    /// Get the ids of the suspended tasks from the top of the stack.
+    pub fn get_task_stack(&self) -> Result<Vec<u32>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT task_id \
+            FROM task_stack \
+            ORDER BY id DESC",
+        )?;
+        let rows = stmt.query_map([], |row| row.get::<_, u32>(0))?;
+
+        let mut ids = Vec::new();
+        for id in rows {
+            ids.push(id?);
+        }
+
+        Ok(ids)
+    }
+
+    /// Record the task paused to be resumed later, unless another task is already paused.
+    pub fn pause_task(&self, task_id: u32) -> Result<()> {
+        let updated = self.conn.execute(
+            "UPDATE manager \
+            SET paused_task_id = ?1 \
+            WHERE id = 0 AND paused_task_id IS NULL",
+            params![task_id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow!("a task is already paused; resume it first"));
+        }
+
+        Ok(())
+    }
+
+    /// Record the task paused to be resumed later, or clear it if `task_id` is `None`.
+    pub fn set_paused_task_id(&self, task_id: Option<u32>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE manager \
+            SET paused_task_id = ?1 \
+            WHERE id = 0",
+            params![task_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Get the id of the paused task.
+    pub fn get_paused_task_id(&self) -> Result<Option<u32>> {
+        let id_or_null = self.conn.query_row(
+            "SELECT paused_task_id \
+            FROM manager",
+            [],
+            |row| row.get::<_, Option<u32>>(0),
+        )?;
+
+        Ok(id_or_null)
+    }
+
+    /// Get manager entry
+    #[allow(clippy::unnecessary_unwrap)]
+    pub fn get_manager(&self) -> Result<Manager> {
+        let manager = self.conn.query_row(
+            "SELECT manager.task_id, task_name, manager.start_time, manager.paused_task_id \
+            FROM manager \
+            LEFT JOIN tasks ON manager.task_id = tasks.id \
+            LEFT JOIN tasknames ON tasks.taskname_id = tasknames.id",
+            [],
+            |row| {
+                let task_id = row.get::<_, Option<u32>>(0)?;
+                let task_name = row.get::<_, Option<String>>(1)?;
+                let start_time_raw = row.get::<_, Option<String>>(2)?;
+                let paused_task_id = row.get::<_, Option<u32>>(3)?;
+                Ok(Manager {
+                    task_id,
+                    task_name,
+                    start_time: if start_time_raw.is_none() {
+                        None
+                    } else {
+                        Some(TaskTime::parse_from_str_iso8601(&start_time_raw.unwrap()).unwrap())
+                    },
+                    paused_task_id,
+                })
+            },
+        )?;
+        Ok(manager)
+    }
+}
+
+/// Marker of the start of a highlighted part in search results.
+pub const HIGHLIGHT_START: &str = "\u{2}";
+
+/// Marker of the end of a highlighted part in search results.
+pub const HIGHLIGHT_END: &str = "\u{3}";
+
+/// A task log matching a search query.
+#[derive(Clone, PartialEq, Eq, Debug, Getters)]
+pub struct SearchHit {
+    #[getset(get = "pub")]
+    task: Task,
+    /// Task name with highlighted matches.
+    #[getset(get = "pub")]
+    name: String,
+    /// Snippet of the note with highlighted matches.
+    #[getset(get = "pub")]
+    note: Option<String>,
+}
+
+/// Project and client names of each task name.
+pub type ProjectMap = HashMap<String, (Option<String>, Option<String>)>;
+
+/// Represents database location, file or memory.
+pub enum DatabaseLocation {
+    Memory,
+    File(PathBuf),
+}
+
+impl fmt::Display for DatabaseLocation {
+    #[allow(clippy::match_ref_pats)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match &self {
+            &DatabaseLocation::Memory => String::from("Memory"),
+            &DatabaseLocation::File(p) => p.to_string_lossy().to_string(),
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// The current task status recorded on `manager` table.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Getters)]
+pub struct Manager {
+    #[getset(get = "pub")]
+    task_id: Option<u32>,
+    #[getset(get = "pub")]
+    task_name: Option<String>,
+    #[getset(get = "pub")]
+    start_time: Option<TaskTime>,
+    #[getset(get = "pub")]
+    paused_task_id: Option<u32>,
+}
+
+/// Migrate task logs to refer to task names by id, adding names only used by task logs as
+/// unregistered ones, and the manager to refer to the running task only by id.
+fn migrate_taskname_ids(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE tasknames RENAME TO tasknames_old", [])?;
+    conn.execute("ALTER TABLE tasks RENAME TO tasks_old", [])?;
+    conn.execute("ALTER TABLE manager RENAME TO manager_old", [])?;
+
+    conn.execute(
+        "CREATE TABLE tasknames (\
+            id INTEGER PRIMARY KEY AUTOINCREMENT,\
+            task_name TEXT NOT NULL UNIQUE,\
+            seq_num INTEGER,\
+            registered INTEGER NOT NULL DEFAULT 1 \
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "INSERT INTO tasknames (task_name, seq_num) \
+        SELECT task_name, min(seq_num) \
+        FROM tasknames_old \
+        GROUP BY task_name",
+        [],
+    )?;
+
+    conn.execute(
+        "INSERT INTO tasknames (task_name, registered) \
+        SELECT DISTINCT name, 0 \
+        FROM tasks_old \
+        WHERE name NOT IN (SELECT task_name FROM tasknames)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE tasks (\
+            id INTEGER PRIMARY KEY AUTOINCREMENT,\
+            taskname_id INTEGER NOT NULL REFERENCES tasknames (id),\
+            working_date TEXT,\
+            seq_num INTEGER,\
+            start_time TEXT,\
+            end_time TEXT, \
+            is_break INTEGER \
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "INSERT INTO tasks (\
+            id, taskname_id, working_date, seq_num, start_time, end_time, is_break\
+        ) \
+        SELECT tasks_old.id, tasknames.id, working_date, tasks_old.seq_num, start_time,\
+            end_time, is_break \
+        FROM tasks_old \
+        JOIN tasknames ON tasks_old.name = tasknames.task_name",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE manager (\
+            id INTEGER PRIMARY KEY AUTOINCREMENT,\
+            task_id INTEGER REFERENCES tasks (id) ON DELETE SET NULL,\
+            start_time TEXT \
+        )",
+        [],
+    )?;
+
+    // 0.5.0 did not reset the manager on deleting the running task
+    conn.execute(
+        "INSERT INTO manager (id, task_id, start_time) \
+        SELECT \
+            id,\
+            CASE WHEN task_id IN (SELECT id FROM tasks) THEN task_id END,\
+            start_time \
+        FROM manager_old",
+        [],
+    )?;
+
+    conn.execute("DROP TABLE manager_old", [])?;
+    conn.execute("DROP TABLE tasks_old", [])?;
+    conn.execute("DROP TABLE tasknames_old", [])?;
+
+    Ok(())
+}
+
+/// Migrate task names to be archived.
+fn migrate_archived_tasknames(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE tasknames \
+        ADD COLUMN archived INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migrate task names to belong to projects of clients.
+fn migrate_projects(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE clients (\
+            id INTEGER PRIMARY KEY AUTOINCREMENT,\
+            client_name TEXT NOT NULL UNIQUE \
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE projects (\
+            id INTEGER PRIMARY KEY AUTOINCREMENT,\
+            project_name TEXT NOT NULL UNIQUE,\
+            client_id INTEGER REFERENCES clients (id) ON DELETE SET NULL \
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "ALTER TABLE tasknames \
+        ADD COLUMN project_id INTEGER REFERENCES projects (id) ON DELETE SET NULL",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migrate task logs to have tags.
+fn migrate_tags(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE tags (\
+            id INTEGER PRIMARY KEY AUTOINCREMENT,\
+            tag_name TEXT NOT NULL UNIQUE \
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE task_tags (\
+            task_id INTEGER NOT NULL REFERENCES tasks (id) ON DELETE CASCADE,\
+            tag_id INTEGER NOT NULL REFERENCES tags (id) ON DELETE CASCADE,\
+            PRIMARY KEY (task_id, tag_id) \
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migrate task logs to have notes.
+fn migrate_notes(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE tasks \
+        ADD COLUMN note TEXT",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migrate task logs to the full-text index, indexing the existing ones.
+fn migrate_fts(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE VIRTUAL TABLE tasks_fts USING fts5(task_name, note)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TRIGGER tasks_fts_insert AFTER INSERT ON tasks \
+        BEGIN \
+            INSERT INTO tasks_fts (rowid, task_name, note) \
+            SELECT new.id, task_name, new.note \
+            FROM tasknames \
+            WHERE id = new.taskname_id; \
+        END",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TRIGGER tasks_fts_update AFTER UPDATE OF taskname_id, note ON tasks \
+        BEGIN \
+            DELETE FROM tasks_fts \
+            WHERE rowid = old.id; \
+            INSERT INTO tasks_fts (rowid, task_name, note) \
+            SELECT new.id, task_name, new.note \
+            FROM tasknames \
+            WHERE id = new.taskname_id; \
+        END",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TRIGGER tasks_fts_delete AFTER DELETE ON tasks \
+        BEGIN \
+            DELETE FROM tasks_fts \
+            WHERE rowid = old.id; \
+        END",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TRIGGER tasks_fts_rename AFTER UPDATE OF task_name ON tasknames \
+        BEGIN \
+            DELETE FROM tasks_fts \
+            WHERE rowid IN (SELECT id FROM tasks WHERE taskname_id = new.id); \
+            INSERT INTO tasks_fts (rowid, task_name, note) \
+            SELECT id, new.task_name, note \
+            FROM tasks \
+            WHERE taskname_id = new.id; \
+        END",
+        [],
+    )?;
+
+    conn.execute(
+        "INSERT INTO tasks_fts (rowid, task_name, note) \
+        SELECT tasks.id, task_name, note \
+        FROM tasks \
+        JOIN tasknames ON tasks.taskname_id = tasknames.id",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migrate task logs to have external references.
+fn migrate_external_refs(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE tasks \
+        ADD COLUMN external_ref TEXT",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migrate task logs to have git repositories and branches.
+fn migrate_repos(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE tasks \
+        ADD COLUMN repo TEXT",
+        [],
+    )?;
+
+    conn.execute(
+        "ALTER TABLE tasks \
+        ADD COLUMN branch TEXT",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migrate directories to be mapped to task names.
+fn migrate_dir_mappings(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE dir_mappings (\
+            id INTEGER PRIMARY KEY AUTOINCREMENT,\
+            pattern TEXT NOT NULL UNIQUE,\
+            taskname_id INTEGER NOT NULL REFERENCES tasknames (id) ON DELETE CASCADE \
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migrate the manager to record the paused task.
+fn migrate_paused_task(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE manager \
+        ADD COLUMN paused_task_id INTEGER REFERENCES tasks (id) ON DELETE SET NULL",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migrate tasks to be suspended by interruptions.
+fn migrate_task_stack(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE task_stack (\
+            id INTEGER PRIMARY KEY AUTOINCREMENT,\
+            task_id INTEGER NOT NULL REFERENCES tasks (id) ON DELETE CASCADE \
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migrate the break flag of task logs to break categories, putting the existing breaks in
+/// the default category.
+fn migrate_break_categories(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE break_categories (\
+            id INTEGER PRIMARY KEY AUTOINCREMENT,\
+            category_name TEXT NOT NULL UNIQUE,\
+            counts_as_work INTEGER NOT NULL DEFAULT 0 \
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "INSERT INTO break_categories (category_name) \
+        VALUES (?1)",
+        params![DEFAULT_BREAK_CATEGORY],
+    )?;
+
+    conn.execute(
+        "ALTER TABLE tasks \
+        ADD COLUMN category_id INTEGER REFERENCES break_categories (id)",
+        [],
+    )?;
+
+    conn.execute(
+        "UPDATE tasks \
+        SET category_id = (SELECT id FROM break_categories WHERE category_name = ?1) \
+        WHERE is_break = 1",
+        params![DEFAULT_BREAK_CATEGORY],
+    )?;
+
+    conn.execute(
+        "ALTER TABLE tasks \
+        DROP COLUMN is_break",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Set the sequence numbers of registered task names ordering by the name.
+fn renumber_tasknames(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "UPDATE tasknames \
+        SET seq_num = NULL \
+        WHERE registered = 0 OR archived = 1",
+        [],
+    )?;
+    conn.execute(
+        "UPDATE tasknames AS a \
+        SET seq_num = n \
+        FROM (\
+            SELECT \
+                id,\
+                row_number() OVER (ORDER BY task_name) AS n \
+            FROM tasknames \
+            WHERE registered = 1 AND archived = 0 \
+        ) AS b \
+        WHERE a.id = b.id",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Set the sequence numbers of tasks on the working date ordering by the start time.
+fn renumber_tasks(conn: &Connection, working_date: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE tasks AS a \
+        SET seq_num = n \
+        FROM (\
+            SELECT \
+                id, \
+                row_number() OVER (ORDER BY start_time) AS n \
+            FROM tasks
+            WHERE working_date = ?1\
+        ) AS b \
+        WHERE a.id = b.id",
+        params![working_date],
+    )?;
+
+    Ok(())
+}
+
+/// Insert a task log with its tags, and return its id.
+fn insert_task(conn: &Connection, task: &Task) -> Result<u32> {
+    let taskname_id = get_or_insert_taskname_id(conn, task.name())?;
+    let category_id = get_break_category_id(conn, task.break_category())?;
+    conn.execute(
+        "INSERT INTO tasks (\
+            taskname_id, working_date, start_time, end_time, category_id, note, external_ref,\
+            repo, branch\
+        ) \
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            taskname_id,
+            task.working_date().to_string(),
+            task.start_time().to_string(),
+            task.end_time().map_or(String::from(""), |t| t.to_string()),
+            category_id,
+            task.note(),
+            task.external_ref(),
+            task.repo(),
+            task.branch()
+        ],
+    )?;
+
+    let task_id = conn.query_row(
+        "SELECT max(id) \
+        FROM tasks",
+        [],
+        |row| Ok(row.get_unwrap::<_, u32>(0)),
+    )?;
+
+    set_task_tags(conn, task_id, task.tags())?;
+
+    Ok(task_id)
+}
+
+/// Overwrite a task log and its tags.
+fn write_task(conn: &Connection, id: u32, task: &Task) -> Result<()> {
+    let taskname_id = get_or_insert_taskname_id(conn, task.name())?;
+    let category_id = get_break_category_id(conn, task.break_category())?;
+    conn.execute(
+        "UPDATE tasks \
+        SET \
+            taskname_id = ?1,\
+            working_date = ?2,\
+            start_time = ?3,\
+            end_time = ?4,\
+            category_id = ?5,\
+            note = ?6,\
+            external_ref = ?7,\
+            repo = ?8,\
+            branch = ?9 \
+        WHERE id = ?10",
+        params![
+            taskname_id,
+            task.working_date().to_string(),
+            task.start_time().to_string(),
+            task.end_time().map_or(String::from(""), |t| t.to_string()),
+            category_id,
+            task.note(),
+            task.external_ref(),
+            task.repo(),
+            task.branch(),
+            id
+        ],
+    )?;
+    set_task_tags(conn, id, task.tags())?;
+
+    Ok(())
+}
+
+/// Get the id of a task name, inserting it as an unregistered name if it does not exist.
+fn get_or_insert_taskname_id(conn: &Connection, task_name: &str) -> Result<u32> {
+    conn.execute(
+        "INSERT OR IGNORE INTO tasknames (task_name, registered) \
+        VALUES (?1, 0)",
+        params![task_name],
+    )?;
+    let id = conn.query_row(
+        "SELECT id \
+        FROM tasknames \
+        WHERE task_name = ?1",
+        params![task_name],
+        |row| row.get::<_, u32>(0),
+    )?;
+
+    Ok(id)
+}
+
+/// Get the id of a break category, or `None` for a working task.
+fn get_break_category_id(
+    conn: &Connection,
+    category: &Option<BreakCategory>,
+) -> Result<Option<u32>> {
+    match category {
+        Some(category) => {
+            let id = conn
+                .query_row(
+                    "SELECT id \
+                    FROM break_categories \
+                    WHERE category_name = ?1",
+                    params![category.name()],
+                    |row| row.get::<_, u32>(0),
+                )
+                .optional()?;
+            id.map(Some)
+                .ok_or_else(|| anyhow!("category does not exist"))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Build an FTS5 query from words, each of which is quoted and matches as a prefix.
+fn build_fts_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Column of the comma-separated tags of each task, selected with the `tasks` table.
+const TAGS_COLUMN: &str = "(\
+    SELECT group_concat(tag_name) \
+    FROM task_tags \
+    JOIN tags ON task_tags.tag_id = tags.id \
+    WHERE task_tags.task_id = tasks.id\
+)";
+
+/// Replace the tags of a task, inserting new tag names.
+fn set_task_tags(conn: &Connection, task_id: u32, tags: &[String]) -> Result<()> {
+    conn.execute(
+        "DELETE FROM task_tags \
+        WHERE task_id = ?1",
+        params![task_id],
+    )?;
+    for tag in tags {
+        conn.execute(
+            "INSERT OR IGNORE INTO tags (tag_name) \
+            VALUES (?1)",
+            params![tag],
+        )?;
+        conn.execute(
+            "INSERT INTO task_tags (task_id, tag_id) \
+            SELECT ?1, id \
+            FROM tags \
+            WHERE tag_name = ?2",
+            params![task_id, tag],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Split the value of `TAGS_COLUMN` into tags.
+fn split_tags(tags: Option<String>) -> Vec<String> {
+    tags.map_or_else(Vec::new, |tags| {
+        tags.split(',').map(|tag| tag.to_string()).collect()
+    })
+}
+
+/// Columns of the break category name and whether it counts as working time, which are
+/// null for working tasks, selected with the `tasks` table.
+const BREAK_CATEGORY_COLUMNS: &str = "\
+    (SELECT category_name FROM break_categories WHERE id = tasks.category_id),\
+    (SELECT counts_as_work FROM break_categories WHERE id = tasks.category_id)";
+
+/// Number of the columns selected by `task_columns`.
+const TASK_COLUMN_COUNT: usize = 11;
+
+/// Columns of a task selected from the `tasks` table joined with `tasknames`,
+/// which are read by `task_from_row`.
+fn task_columns() -> String {
+    format!(
+        "tasks.id, tasknames.task_name, tasks.start_time, tasks.end_time, {}, \
+        tasks.note, tasks.external_ref, tasks.repo, tasks.branch, {}",
+        BREAK_CATEGORY_COLUMNS, TAGS_COLUMN
+    )
+}
+
+/// Build a task from the columns of `task_columns` starting at the index `first` of a row.
+fn task_from_row(row: &Row, first: usize) -> rusqlite::Result<Task> {
+    let id = row.get::<_, u32>(first)?;
+    let name = row.get_unwrap::<_, String>(first + 1);
+    let start_time =
+        TaskTime::parse_from_str_iso8601(&row.get_unwrap::<_, String>(first + 2)).unwrap();
+    let end_time = TaskTime::parse_from_str_iso8601(&row.get_unwrap::<_, String>(first + 3)).ok();
+    let break_category = row
+        .get_unwrap::<_, Option<String>>(first + 4)
+        .map(|name| BreakCategory::new(name, row.get_unwrap::<_, bool>(first + 5)));
+    let note = row.get_unwrap::<_, Option<String>>(first + 6);
+    let external_ref = row.get_unwrap::<_, Option<String>>(first + 7);
+    let repo = row.get_unwrap::<_, Option<String>>(first + 8);
+    let branch = row.get_unwrap::<_, Option<String>>(first + 9);
+    let tags = split_tags(row.get_unwrap::<_, Option<String>>(first + 10));
+
+    Ok(
+        Task::new(Some(id), name, start_time, end_time, break_category)
+            .with_tags(tags)
+            .with_note(note)
+            .with_external_ref(external_ref)
+            .with_repo(repo, branch),
+    )
+}
+
+/// Build a pair of a sequence number and a task from a row of the `tasks` table.
+fn task_with_seqnum_from_row(row: &Row) -> rusqlite::Result<(u32, Task)> {
+    let seq_num = row.get_unwrap::<_, u32>(0);
+
+    Ok((seq_num, task_from_row(row, 1)?))
+}
+
+/// Get the database path from the environment variable `TASKLOG_DB_PATH`,
+/// or default file in the current directory.
+pub fn get_db_path_from_env_var_or(default: &str) -> std::io::Result<PathBuf> {
+    match env::var("TASKLOG_DB_PATH") {
+        Ok(path) => Ok(PathBuf::from(path)),
+        Err(_) => {
+            let mut path = env::current_dir()?;
+            path.push(default);
+            Ok(path)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Result;
+    use std::error::Error;
+
+    fn setup_db() -> Result<Database, Box<dyn Error>> {
+        let mut db = Database::new(Connection::open_in_memory()?, DatabaseLocation::Memory)?;
+        db.initialize()?;
+        Ok(db)
+    }
+
+    #[test]
+    fn test_database_is_not_ready() -> Result<(), Box<dyn Error>> {
+        let db = Database::new(Connection::open_in_memory()?, DatabaseLocation::Memory)?;
+
+        assert!(!db.is_ready()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_database_is_ready() -> Result<(), Box<dyn Error>> {
+        let mut db = Database::new(Connection::open_in_memory()?, DatabaseLocation::Memory)?;
+
+        db.initialize()?;
+        assert!(db.is_ready()?);
+        assert!(!db.is_outdated()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_database_is_outdated() -> Result<(), Box<dyn Error>> {
+        let db = Database::new(Connection::open_in_memory()?, DatabaseLocation::Memory)?;
+
+        db.conn.execute(
+            "CREATE TABLE tasks (\
+                id INTEGER PRIMARY KEY AUTOINCREMENT,\
+                name TEXT \
+            )",
+            [],
+        )?;
+        assert!(db.is_ready()?);
+        assert!(db.is_outdated()?);
+
+        Ok(())
+    }
+
+    /// Create a database with the tables created by 0.5.0.
+    fn setup_old_db() -> Result<Database, Box<dyn Error>> {
+        let db = Database::new(Connection::open_in_memory()?, DatabaseLocation::Memory)?;
+        db.conn.execute_batch(
+            "CREATE TABLE tasks (\
+                id INTEGER PRIMARY KEY AUTOINCREMENT,\
+                name TEXT,\
+                working_date TEXT,\
+                seq_num INTEGER,\
+                start_time TEXT,\
+                end_time TEXT, \
+                is_break INTEGER \
+            );\
+            CREATE TABLE tasknames (\
+                id INTEGER PRIMARY KEY AUTOINCREMENT,\
+                task_name TEXT,\
+                seq_num INTEGER \
+            );\
+            CREATE TABLE manager (\
+                id INTEGER PRIMARY KEY AUTOINCREMENT,\
+                task_id INTEGER,\
+                task_name TEXT,\
+                start_time TEXT \
+            );",
+        )?;
+        Ok(db)
+    }
+
+    #[test]
+    fn test_migrate_from_old_schema() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_old_db()?;
+
+        db.conn.execute_batch(
+            "INSERT INTO tasknames (task_name, seq_num) VALUES ('task b', 1), ('task a', 2);\
+            INSERT INTO tasks (name, working_date, seq_num, start_time, end_time, is_break) \
+            VALUES \
+                ('task a', '2021-01-01', 1, '2021-01-01T10:00:00', '2021-01-01T11:00:00', 0),\
+                ('break time', '2021-01-01', 2, '2021-01-01T11:00:00', '2021-01-01T11:30:00', 1),\
+                ('task c', '2021-01-01', 3, '2021-01-01T11:30:00', '', 0);\
+            INSERT INTO manager (id, task_id, task_name, start_time) \
+            VALUES (0, 3, 'task c', '2021-01-01T11:30:00');",
+        )?;
+        assert!(db.is_outdated()?);
+
+        db.migrate()?;
+        assert!(!db.is_outdated()?);
+
+        assert_eq!(
+            db.get_registered_tasknames()?,
+            vec![(1, String::from("task a")), (2, String::from("task b"))]
+        );
+
+        let tasks = db.get_tasks(true, None)?.into_iter().collect::<Vec<_>>();
+        let names = tasks
+            .iter()
+            .map(|(seq_num, task)| (*seq_num, task.name().as_str(), task.is_break_time()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                (1, "task a", false),
+                (2, "break time", true),
+                (3, "task c", false)
+            ]
+        );
+        assert_eq!(
+            tasks[1]
+                .1
+                .break_category()
+                .as_ref()
+                .map(|c| c.name().as_str()),
+            Some(DEFAULT_BREAK_CATEGORY)
+        );
+
+        let manager = db.get_manager()?;
+        assert_eq!(manager.task_id(), &Some(3));
+        assert_eq!(manager.task_name(), &Some(String::from("task c")));
+
+        assert_eq!(db.search_tasks("task", 10)?.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_manager_of_deleted_task() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_old_db()?;
+
+        // the running task 2 has been deleted, which 0.5.0 did not reset the manager on
+        db.conn.execute_batch(
+            "INSERT INTO tasks (name, working_date, seq_num, start_time, end_time, is_break) \
+            VALUES ('task a', '2021-01-01', 1, '2021-01-01T10:00:00', '2021-01-01T11:00:00', 0);\
+            INSERT INTO manager (id, task_id, task_name, start_time) \
+            VALUES (0, 2, 'task b', '2021-01-01T11:00:00');",
+        )?;
+
+        db.migrate()?;
+        assert!(!db.is_outdated()?);
+        assert_eq!(db.get_manager()?.task_id(), &None);
+        assert_eq!(db.get_tasks(true, None)?.into_iter().count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_register_new_name() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db()?;
+
+        db.register_taskname("task b")?;
+        db.register_taskname("task a")?;
+        let mut stmt = db
+            .conn
+            .prepare("SELECT seq_num, task_name FROM tasknames ORDER BY seq_num")?;
+        let mut rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?))
+        })?;
+        assert_eq!(rows.next().unwrap()?, (1, String::from("task a")));
+        assert_eq!(rows.next().unwrap()?, (2, String::from("task b")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_register_existing_name() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db()?;
+
+        db.register_taskname("task a")?;
+        assert!(db.register_taskname("task a").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unregister_new_name() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db()?;
+
+        db.register_taskname("task b")?;
+        db.register_taskname("task a")?;
+        db.unregister_taskname("task a")?;
+        let mut stmt = db
+            .conn
+            .prepare("SELECT seq_num, task_name FROM tasknames ORDER BY seq_num")?;
+        let mut rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?))
+        })?;
+        assert_eq!(rows.next().unwrap()?, (1, String::from("task b")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unregister_used_name() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db()?;
+
+        db.register_taskname("task a")?;
+        let start_time = chrono::NaiveDate::from_ymd(2021, 1, 1).and_hms(10, 50, 21);
+        let task = Task::start(String::from("task a"), TaskTime::from(start_time), None);
+        db.add_task_entry(&task)?;
+
+        // the name is hidden from the list but task logs still refer to it
+        db.unregister_taskname("task a")?;
+        assert_eq!(db.get_registered_tasknames()?, vec![]);
+        assert_eq!(db.get_task(1)?.name(), "task a");
+        assert!(db.unregister_taskname("task a").is_err());
+
+        db.register_taskname("task a")?;
+        assert_eq!(
+            db.get_registered_tasknames()?,
+            vec![(1, String::from("task a"))]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_task_entry_refers_to_taskname_id() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db()?;
+
+        db.register_taskname("task a")?;
+        let start_time = chrono::NaiveDate::from_ymd(2021, 1, 1).and_hms(10, 50, 21);
+        let task = Task::start(String::from("task a"), TaskTime::from(start_time), None);
+        db.add_task_entry(&task)?;
+        let task = Task::start(
+            String::from("break"),
+            TaskTime::from(start_time),
+            Some(BreakCategory::new(String::from("break time"), false)),
+        );
+        db.add_task_entry(&task)?;
+
+        // names used only by task logs are not registered
+        assert_eq!(
+            db.get_registered_tasknames()?,
+            vec![(1, String::from("task a"))]
+        );
+
+        // renaming propagates to task logs
+        db.conn.execute(
+            "UPDATE tasknames SET task_name = 'task b' WHERE task_name = 'task a'",
+            [],
+        )?;
+        assert_eq!(db.get_task(1)?.name(), "task b");
+
+        // a referred name can not be deleted
+        assert!(db
+            .conn
+            .execute("DELETE FROM tasknames WHERE task_name = 'task b'", [])
+            .is_err());
+
+        Ok(())
+    }
+
+    /// Register names and add a task log for each of them.
+    fn setup_db_with_tasknames(names: &[&str]) -> Result<Database, Box<dyn Error>> {
+        let mut db = setup_db()?;
+
+        for (i, name) in names.iter().enumerate() {
+            db.register_taskname(name)?;
+            let start_time = chrono::NaiveDate::from_ymd(2021, 1, 1).and_hms(10 + i as u32, 0, 0);
+            let task = Task::start(name.to_string(), TaskTime::from(start_time), None);
+            db.add_task_entry(&task)?;
+        }
+
+        Ok(db)
+    }
+
+    #[test]
+    fn test_rename_taskname() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db_with_tasknames(&["task a", "task b"])?;
+
+        assert!(!db.rename_taskname("task a", "task c", false)?);
+        assert_eq!(
+            db.get_registered_tasknames()?,
+            vec![(1, String::from("task b")), (2, String::from("task c"))]
+        );
+        assert_eq!(db.get_task(1)?.name(), "task c");
+
+        assert!(db.rename_taskname("task x", "task y", false).is_err());
+        assert!(db.rename_taskname("task b", "task b", false).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_taskname_merge() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db_with_tasknames(&["task a", "task b"])?;
+
+        assert!(db.rename_taskname("task a", "task b", false)?);
+        assert_eq!(
+            db.get_registered_tasknames()?,
+            vec![(1, String::from("task b"))]
+        );
+        assert_eq!(db.get_task(1)?.name(), "task b");
+        assert_eq!(db.get_task(2)?.name(), "task b");
+        let count = db.conn.query_row(
+            "SELECT count(id) FROM tasknames WHERE task_name = 'task a'",
+            [],
+            |row| row.get::<_, u32>(0),
+        )?;
+        assert_eq!(count, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_taskname_keep_history() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db_with_tasknames(&["task a", "task b"])?;
+
+        assert!(!db.rename_taskname("task a", "task c", true)?);
+        assert_eq!(
+            db.get_registered_tasknames()?,
+            vec![(1, String::from("task b")), (2, String::from("task c"))]
+        );
+        assert_eq!(db.get_task(1)?.name(), "task a");
+
+        assert!(db.rename_taskname("task b", "task c", true)?);
+        assert_eq!(
+            db.get_registered_tasknames()?,
+            vec![(1, String::from("task c"))]
+        );
+        assert_eq!(db.get_task(2)?.name(), "task b");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_archive_taskname() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db_with_tasknames(&["task a", "task b"])?;
+
+        db.set_taskname_archived("task a", true)?;
+        assert_eq!(
+            db.get_registered_tasknames()?,
+            vec![(1, String::from("task b"))]
+        );
+        assert_eq!(db.get_archived_tasknames()?, vec![String::from("task a")]);
+        assert_eq!(db.get_task(1)?.name(), "task a");
+        assert!(db.set_taskname_archived("task a", true).is_err());
+        assert!(db.register_taskname("task a").is_err());
+
+        db.set_taskname_archived("task a", false)?;
+        assert_eq!(
+            db.get_registered_tasknames()?,
+            vec![(1, String::from("task a")), (2, String::from("task b"))]
+        );
+        assert_eq!(db.get_archived_tasknames()?, Vec::<String>::new());
+        assert!(db.set_taskname_archived("task a", false).is_err());
+        assert!(db.set_taskname_archived("task x", true).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_project_and_client() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db_with_tasknames(&["task a", "task b", "task c"])?;
+
+        db.add_project("project x", Some("client p"))?;
+        db.add_project("project y", None)?;
+        assert_eq!(
+            db.get_projects()?,
+            vec![
+                (String::from("project x"), Some(String::from("client p"))),
+                (String::from("project y"), None)
+            ]
+        );
+
+        db.add_project("project y", Some("client q"))?;
+        db.assign_project("task a", Some("project x"))?;
+        db.assign_project("task b", Some("project y"))?;
+        assert!(db.assign_project("task a", Some("project z")).is_err());
+        assert!(db.assign_project("task x", Some("project x")).is_err());
+
+        let map = db.get_taskname_projects()?;
+        assert_eq!(
+            map.get("task a"),
+            Some(&(
+                Some(String::from("project x")),
+                Some(String::from("client p"))
+            ))
+        );
+        assert_eq!(
+            map.get("task b"),
+            Some(&(
+                Some(String::from("project y")),
+                Some(String::from("client q"))
+            ))
+        );
+        assert_eq!(map.get("task c"), Some(&(None, None)));
+
+        db.assign_project("task a", None)?;
+        assert_eq!(
+            db.get_taskname_projects()?.get("task a"),
+            Some(&(None, None))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_task_tags() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db()?;
+
+        let start_time = chrono::NaiveDate::from_ymd(2021, 1, 1).and_hms(10, 0, 0);
+        let task = Task::start(String::from("task a"), TaskTime::from(start_time), None)
+            .with_tags(vec![String::from("review"), String::from("meeting")]);
+        db.add_task_entry(&task)?;
+        assert_eq!(
+            db.get_task(1)?.tags(),
+            &vec![String::from("meeting"), String::from("review")]
+        );
+
+        let task = db
+            .get_task(1)?
+            .change_tags(&[String::from("oncall")], &[String::from("review")]);
+        db.update_task(1, &task)?;
+        let tasks = db.get_tasks(true, None)?;
+        let (_, task) = tasks.into_iter().next().unwrap();
+        assert_eq!(
+            task.tags(),
+            &vec![String::from("meeting"), String::from("oncall")]
+        );
+
+        // tags of a deleted task are removed with it
+        db.delete_task(1)?;
+        let count = db
+            .conn
+            .query_row("SELECT count(*) FROM task_tags", [], |row| {
+                row.get::<_, u32>(0)
+            })?;
+        assert_eq!(count, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_task_note() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db()?;
+
+        let start_time = chrono::NaiveDate::from_ymd(2021, 1, 1).and_hms(10, 0, 0);
+        let task = Task::start(String::from("task a"), TaskTime::from(start_time), None)
+            .with_note(Some(String::from("fix the login bug")));
+        db.add_task_entry(&task)?;
+        assert_eq!(
+            db.get_task(1)?.note(),
+            &Some(String::from("fix the login bug"))
+        );
+
+        let mut task = db.get_task(1)?;
+        task.set_note(None);
+        db.update_task(1, &task)?;
+        let (_, task) = db.get_tasks(true, None)?.into_iter().next().unwrap();
+        assert_eq!(task.note(), &None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_task_external_ref() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db()?;
+
+        let start_time = chrono::NaiveDate::from_ymd(2021, 1, 1).and_hms(10, 0, 0);
+        let task = Task::start(String::from("task a"), TaskTime::from(start_time), None)
+            .with_external_ref(Some(String::from("PROJ-1")));
+        db.add_task_entry(&task)?;
+        assert_eq!(
+            db.get_task(1)?.external_ref(),
+            &Some(String::from("PROJ-1"))
+        );
+
+        let task = db
+            .get_task(1)?
+            .with_external_ref(Some(String::from("PROJ-2")));
+        db.update_task(1, &task)?;
+        let (_, task) = db.get_tasks(true, None)?.into_iter().next().unwrap();
+        assert_eq!(task.external_ref(), &Some(String::from("PROJ-2")));
 
-            Ok((
-                seq_num,
-                Task::new(Some(id), name, start_time, end_time, is_break_time),
-            ))
-        })?;
+        Ok(())
+    }
 
-        let mut task_list = Vec::new();
-        for tuple in rows {
-            task_list.push(tuple?);
-        }
+    #[test]
+    fn test_task_repo() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db()?;
 
-        Ok(TaskList::new(task_list))
-    }
+        let start_time = chrono::NaiveDate::from_ymd(2021, 1, 1).and_hms(10, 0, 0);
+        let task = Task::start(String::from("task a"), TaskTime::from(start_time), None)
+            .with_repo(Some(String::from("/src/app")), Some(String::from("main")));
+        db.add_task_entry(&task)?;
 
-    /// Update a task log in the database.
-    pub fn update_task(&self, id: u32, updated_task: &Task) -> Result<()> {
-        self.conn.execute(
-            "UPDATE tasks \
-            SET \
-                name = ?1,\
-                working_date = ?2,\
-                start_time = ?3,\
-                end_time = ?4,\
-                is_break = ?5 \
-            WHERE id = ?6",
-            params![
-                updated_task.name(),
-                updated_task.working_date().to_string(),
-                updated_task.start_time().to_string(),
-                updated_task
-                    .end_time()
-                    .map_or(String::from(""), |t| t.to_string()),
-                *updated_task.is_break_time() as i32,
-                id
-            ],
-        )?;
+        let task = db.get_task(1)?;
+        assert_eq!(task.repo(), &Some(String::from("/src/app")));
+        assert_eq!(task.branch(), &Some(String::from("main")));
 
         Ok(())
     }
 
-    /// Delete a task.
-    pub fn delete_task(&mut self, id: u32) -> Result<()> {
-        let working_date = self.get_task(id)?.working_date().to_string();
+    #[test]
+    fn test_break_categories() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db()?;
 
-        let tx = self.conn.transaction()?;
+        let lunch = BreakCategory::new(String::from("lunch"), false);
+        let commute = BreakCategory::new(String::from("commute"), true);
+        db.add_break_category("lunch", true)?;
+        db.add_break_category("lunch", false)?;
+        db.add_break_category("commute", true)?;
+        assert_eq!(
+            db.get_break_categories()?,
+            vec![
+                BreakCategory::new(String::from(DEFAULT_BREAK_CATEGORY), false),
+                commute.clone(),
+                lunch.clone()
+            ]
+        );
+        assert_eq!(db.get_break_category("lunch")?, Some(lunch.clone()));
+        assert_eq!(db.get_break_category("sick")?, None);
 
-        &tx.execute(
-            "DELETE FROM tasks
-            WHERE id = ?1",
-            params![id],
-        )?;
+        let start_time = chrono::NaiveDate::from_ymd(2021, 1, 1).and_hms(12, 0, 0);
+        let task = Task::start(
+            String::from("lunch"),
+            TaskTime::from(start_time),
+            Some(lunch),
+        );
+        db.add_task_entry(&task)?;
+        let mut task = db.get_task(1)?;
+        assert_eq!(task.break_category().as_ref().unwrap().name(), "lunch");
 
-        &tx.execute(
-            "UPDATE tasks AS a \
-            SET seq_num = n \
-            FROM (\
-                SELECT \
-                    id, \
-                    row_number() OVER (ORDER BY start_time) AS n \
-                FROM tasks
-                WHERE working_date = ?1\
-            ) AS b \
-            WHERE a.id = b.id",
-            params![working_date],
-        )?;
+        task.set_break_category(Some(commute));
+        db.update_task(1, &task)?;
+        assert!(db.get_task(1)?.is_working());
 
-        &tx.commit()?;
+        // a category which is not added can not be used
+        let task = Task::start(
+            String::from("sick"),
+            TaskTime::from(start_time),
+            Some(BreakCategory::new(String::from("sick"), false)),
+        );
+        assert!(db.add_task_entry(&task).is_err());
 
         Ok(())
     }
 
-    /// Reset manager entry.
-    pub fn reset_manager(&self) -> Result<()> {
-        self.conn.execute(
-            "UPDATE manager \
-            SET \
-                task_id = NULL, \
-                task_name = NULL, \
-                start_time = NULL \
-            WHERE id = 0",
-            [],
-        )?;
+    #[test]
+    fn test_get_recent_tasknames() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db_with_tasknames(&["task a", "task b", "task c"])?;
+
+        let start_time = chrono::NaiveDate::from_ymd(2021, 1, 1).and_hms(15, 0, 0);
+        let task = Task::start(String::from("task a"), TaskTime::from(start_time), None);
+        db.add_task_entry(&task)?;
+        let start_time = chrono::NaiveDate::from_ymd(2021, 1, 1).and_hms(16, 0, 0);
+        let task = Task::start(
+            String::from(DEFAULT_BREAK_CATEGORY),
+            TaskTime::from(start_time),
+            Some(BreakCategory::new(
+                String::from(DEFAULT_BREAK_CATEGORY),
+                false,
+            )),
+        );
+        db.add_task_entry(&task)?;
+
+        assert_eq!(
+            db.get_recent_tasknames(10)?,
+            vec![
+                String::from("task a"),
+                String::from("task c"),
+                String::from("task b")
+            ]
+        );
+        assert_eq!(db.get_recent_tasknames(1)?, vec![String::from("task a")]);
+
+        // archived names are omitted
+        db.set_taskname_archived("task c", true)?;
+        assert_eq!(
+            db.get_recent_tasknames(10)?,
+            vec![String::from("task a"), String::from("task b")]
+        );
 
         Ok(())
     }
 
-    /// Get manager entry
-    pub fn get_manager(&self) -> Result<Manager> {
-        let manager = self.conn.query_row(
-            "SELECT task_id, task_name, start_time \
-            FROM manager",
-            [],
-            |row| {
-                let task_id = row.get::<_, Option<u32>>(0)?;
-                let task_name = row.get::<_, Option<String>>(1)?;
-                let start_time_raw = row.get::<_, Option<String>>(2)?;
-                Ok(Manager {
-                    task_id,
-                    task_name,
-                    start_time: if start_time_raw.is_none() {
-                        None
-                    } else {
-                        Some(TaskTime::parse_from_str_iso8601(&start_time_raw.unwrap()).unwrap())
-                    },
-                })
-            },
-        )?;
-        Ok(manager)
+    #[test]
+    fn test_build_fts_query() {
+        assert_eq!(build_fts_query("login bug"), "\"login\"* \"bug\"*");
+        assert_eq!(build_fts_query(" say \"hi\" "), "\"say\"* \"\"\"hi\"\"\"*");
     }
-}
 
-/// Represents database location, file or memory.
-pub enum DatabaseLocation {
-    Memory,
-    File(PathBuf),
-}
+    #[test]
+    fn test_search_tasks() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db_with_tasknames(&["task a", "task b"])?;
 
-impl fmt::Display for DatabaseLocation {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let s = match &self {
-            &DatabaseLocation::Memory => String::from("Memory"),
-            &DatabaseLocation::File(p) => p.to_string_lossy().to_string(),
-        };
-        write!(f, "{}", s)
+        let mut task = db.get_task(1)?;
+        task.set_note(Some(String::from("fixed the login bug")));
+        db.update_task(1, &task)?;
+
+        let hits = db.search_tasks("log", 10)?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].task().id(), &Some(1));
+        assert_eq!(hits[0].name(), "task a");
+        assert_eq!(
+            hits[0].note(),
+            &Some(format!(
+                "fixed the {}login{} bug",
+                HIGHLIGHT_START, HIGHLIGHT_END
+            ))
+        );
+
+        // the index follows renames and deletions, and the recent one comes first
+        db.rename_taskname("task b", "login page", false)?;
+        let hits = db.search_tasks("login", 10)?;
+        assert_eq!(
+            hits.iter().map(|hit| *hit.task().id()).collect::<Vec<_>>(),
+            vec![Some(2), Some(1)]
+        );
+        assert_eq!(
+            hits[0].name(),
+            &format!("{}login{} page", HIGHLIGHT_START, HIGHLIGHT_END)
+        );
+
+        db.delete_task(1)?;
+        assert_eq!(db.search_tasks("bug", 10)?, vec![]);
+
+        Ok(())
     }
-}
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
-pub struct Manager {
-    task_id: Option<u32>,
-    task_name: Option<String>,
-    start_time: Option<TaskTime>,
-}
+    #[test]
+    fn test_dir_mappings() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db()?;
 
-/// Get the database path from the environment variable `TASKLOG_DB_PATH`,
-/// or default file in the current directory.
-pub fn get_db_path_from_env_var_or(default: &str) -> std::io::Result<PathBuf> {
-    match env::var("TASKLOG_DB_PATH") {
-        Ok(path) => Ok(PathBuf::from(path)),
-        Err(_) => {
-            let mut path = env::current_dir()?;
-            path.push(default);
-            Ok(path)
-        }
+        db.register_taskname("billing")?;
+        db.register_taskname("payroll")?;
+        db.add_dir_mapping("/work/billing", "billing")?;
+        db.add_dir_mapping("/work/payroll/**", "billing")?;
+        db.add_dir_mapping("/work/payroll/**", "payroll")?;
+        assert!(db.add_dir_mapping("/work/other", "other").is_err());
+        assert_eq!(
+            db.get_dir_mappings()?,
+            vec![
+                (String::from("/work/billing"), String::from("billing")),
+                (String::from("/work/payroll/**"), String::from("payroll"))
+            ]
+        );
+
+        // mappings to archived or unregistered names are ignored
+        db.set_taskname_archived("payroll", true)?;
+        db.unregister_taskname("billing")?;
+        assert_eq!(db.get_dir_mappings()?, vec![]);
+
+        db.remove_dir_mapping("/work/payroll/**")?;
+        assert!(db.remove_dir_mapping("/work/payroll/**").is_err());
+
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use rusqlite::Result;
-    use std::error::Error;
+    #[test]
+    fn test_reopen_task() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db_with_tasknames(&["task a", "task b"])?;
 
-    fn setup_db() -> Result<Database, Box<dyn Error>> {
-        let mut db = Database {
-            conn: Connection::open_in_memory()?,
-            location: DatabaseLocation::Memory,
-        };
-        db.initialize()?;
-        Ok(db)
+        let end_time = TaskTime::from(chrono::NaiveDate::from_ymd(2021, 1, 1).and_hms(12, 0, 0));
+        let task = db.get_task(2)?.end(end_time)?;
+        db.update_task(2, &task)?;
+        db.reset_manager()?;
+
+        db.reopen_task(2)?;
+        assert_eq!(db.get_task(2)?.end_time(), &None);
+        let manager = db.get_manager()?;
+        assert_eq!(manager.task_id(), &Some(2));
+        assert_eq!(manager.start_time(), &Some(*db.get_task(2)?.start_time()));
+
+        Ok(())
     }
 
     #[test]
-    fn test_database_is_not_ready() -> Result<(), Box<dyn Error>> {
-        let db = Database {
-            conn: Connection::open_in_memory()?,
-            location: DatabaseLocation::Memory,
-        };
+    fn test_split_task() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db_with_tasknames(&["task a", "task b"])?;
+
+        // split the running task
+        let time = TaskTime::from(chrono::NaiveDate::from_ymd(2021, 1, 1).and_hms(11, 30, 0));
+        let (first, mut second) = db.get_task(2)?.split_at(time)?;
+        second.set_name(String::from("task c"));
+        db.split_task(2, &first, &second)?;
+
+        assert_eq!(db.get_task(2)?.end_time(), &Some(time));
+        assert_eq!(db.get_task(3)?.name(), "task c");
+        assert_eq!(db.get_current_task_id()?, Some(3));
+        let working_date = db.get_task(1)?.working_date();
+        assert_eq!(db.get_task_id_by_seqnum(3, working_date)?, 3);
+
+        // split the ended task before it
+        let time = TaskTime::from(chrono::NaiveDate::from_ymd(2021, 1, 1).and_hms(10, 30, 0));
+        let task = db.get_task(1)?.end(TaskTime::from(
+            chrono::NaiveDate::from_ymd(2021, 1, 1).and_hms(11, 0, 0),
+        ))?;
+        let (first, second) = task.split_at(time)?;
+        db.split_task(1, &first, &second)?;
+
+        assert_eq!(db.get_task_id_by_seqnum(2, working_date)?, 4);
+        assert_eq!(db.get_task_id_by_seqnum(3, working_date)?, 2);
+        assert_eq!(db.get_current_task_id()?, Some(3));
 
-        assert!(!db.is_ready()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_tasks() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db_with_tasknames(&["task a", "task b", "task c"])?;
+
+        let task1 = db.get_task(1)?.end(*db.get_task(2)?.start_time())?;
+        let task2 = db.get_task(2)?.end(*db.get_task(3)?.start_time())?;
+        let merged = task1.merge(&task2).merge(&db.get_task(3)?);
+        db.merge_tasks(1, &merged, &[2, 3])?;
+
+        let working_date = merged.working_date();
+        let tasks = db.get_tasks(false, Some(working_date))?;
+        assert_eq!(tasks, TaskList::new(vec![(1, merged)]));
+        assert_eq!(db.get_current_task_id()?, Some(1));
 
         Ok(())
     }
 
     #[test]
-    fn test_database_is_ready() -> Result<(), Box<dyn Error>> {
-        let mut db = Database {
-            conn: Connection::open_in_memory()?,
-            location: DatabaseLocation::Memory,
-        };
+    fn test_insert_task_entry() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db_with_tasknames(&["task a", "task b"])?;
 
-        db.initialize()?;
-        assert!(db.is_ready()?);
+        let start_time = TaskTime::from(chrono::NaiveDate::from_ymd(2021, 1, 1).and_hms(9, 0, 0));
+        let end_time = TaskTime::from(chrono::NaiveDate::from_ymd(2021, 1, 1).and_hms(10, 0, 0));
+        let task = Task::new(
+            None,
+            String::from("task b"),
+            start_time,
+            Some(end_time),
+            None,
+        );
+        db.insert_task_entry(&task)?;
+
+        assert_eq!(db.get_current_task_id()?, Some(2));
+        assert_eq!(db.get_task_id_by_seqnum(1, task.working_date())?, 3);
 
         Ok(())
     }
 
     #[test]
-    fn test_register_new_name() -> Result<(), Box<dyn Error>> {
-        let mut db = setup_db()?;
+    fn test_paused_task_id() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db_with_tasknames(&["task a"])?;
 
-        db.register_taskname("task b")?;
-        db.register_taskname("task a")?;
-        let mut stmt = db
-            .conn
-            .prepare("SELECT seq_num, task_name FROM tasknames ORDER BY seq_num")?;
-        let mut rows = stmt.query_map([], |row| {
-            Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?))
-        })?;
-        assert_eq!(rows.next().unwrap()?, (1, String::from("task a")));
-        assert_eq!(rows.next().unwrap()?, (2, String::from("task b")));
+        assert_eq!(db.get_paused_task_id()?, None);
+        db.reset_manager()?;
+        db.set_paused_task_id(Some(1))?;
+        assert_eq!(db.get_paused_task_id()?, Some(1));
+        assert_eq!(db.get_manager()?.task_id(), &None);
+
+        // deleting the paused task clears it
+        db.delete_task(1)?;
+        assert_eq!(db.get_paused_task_id()?, None);
 
         Ok(())
     }
 
     #[test]
-    fn test_register_existing_name() -> Result<(), Box<dyn Error>> {
-        let mut db = setup_db()?;
+    fn test_pause_task_twice() -> Result<(), Box<dyn Error>> {
+        let db = setup_db_with_tasknames(&["task a", "task b"])?;
 
-        db.register_taskname("task a")?;
-        assert!(db.register_taskname("task a").is_err());
+        db.pause_task(1)?;
+        assert!(db.pause_task(2).is_err());
+        assert_eq!(db.get_paused_task_id()?, Some(1));
+
+        // another task can be paused after resuming
+        db.set_paused_task_id(None)?;
+        db.pause_task(2)?;
+        assert_eq!(db.get_paused_task_id()?, Some(2));
 
         Ok(())
     }
 
     #[test]
-    fn test_unregister_new_name() -> Result<(), Box<dyn Error>> {
-        let mut db = setup_db()?;
+    fn test_task_stack() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db_with_tasknames(&["task a", "task b", "task c"])?;
 
-        db.register_taskname("task b")?;
-        db.register_taskname("task a")?;
-        db.unregister_taskname("task a")?;
-        let mut stmt = db
-            .conn
-            .prepare("SELECT seq_num, task_name FROM tasknames ORDER BY seq_num")?;
-        let mut rows = stmt.query_map([], |row| {
-            Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?))
-        })?;
-        assert_eq!(rows.next().unwrap()?, (1, String::from("task b")));
+        assert_eq!(db.pop_task_stack()?, None);
+        db.push_task_stack(1)?;
+        db.push_task_stack(2)?;
+        assert_eq!(db.get_task_stack()?, vec![2, 1]);
+
+        assert_eq!(db.pop_task_stack()?, Some(2));
+        assert_eq!(db.get_task_stack()?, vec![1]);
+
+        // a deleted task is removed from the stack
+        db.delete_task(1)?;
+        assert_eq!(db.get_task_stack()?, vec![]);
 
         Ok(())
     }
@@ -648,6 +2771,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_add_task_entry() -> Result<(), Box<dyn Error>> {
         let mut db = setup_db()?;
 
@@ -657,13 +2781,14 @@ mod tests {
             String::from("task a"),
             TaskTime::from(start_time),
             None,
-            false,
+            None,
         );
         db.add_task_entry(&task)?;
 
         // `tasks` table
         let id = db.conn.query_row(
-            "SELECT name, working_date, seq_num, start_time, end_time, is_break, id FROM tasks",
+            "SELECT task_name, working_date, tasks.seq_num, start_time, end_time, category_id IS NOT NULL, tasks.id \
+            FROM tasks JOIN tasknames ON tasks.taskname_id = tasknames.id",
             [],
             |row| {
                 assert_eq!(row.get::<_, String>(0)?, String::from("task a"));
@@ -683,7 +2808,10 @@ mod tests {
 
         // `manager` table
         db.conn.query_row(
-            "SELECT id, task_id, task_name, start_time FROM manager",
+            "SELECT manager.id, task_id, task_name, manager.start_time \
+            FROM manager \
+            JOIN tasks ON manager.task_id = tasks.id \
+            JOIN tasknames ON tasks.taskname_id = tasknames.id",
             [],
             |row| {
                 assert_eq!(row.get::<_, u32>(0)?, 0);
@@ -712,7 +2840,7 @@ mod tests {
             String::from("task a"),
             TaskTime::from(start_time),
             None,
-            false,
+            None,
         );
         db.add_task_entry(&task)?;
         assert_eq!(db.get_current_task_id()?.unwrap(), 1);
@@ -723,7 +2851,7 @@ mod tests {
             String::from("task b"),
             TaskTime::from(start_time),
             None,
-            false,
+            None,
         );
         db.add_task_entry(&task)?;
         assert_eq!(db.get_current_task_id()?.unwrap(), 2);
@@ -741,7 +2869,7 @@ mod tests {
                 String::from("task a"),
                 TaskTime::from(start_time),
                 None,
-                false,
+                None,
             );
             db.add_task_entry(&task)?;
         }
@@ -752,7 +2880,7 @@ mod tests {
                 String::from("task b"),
                 TaskTime::from(start_time),
                 None,
-                false,
+                None,
             );
             db.add_task_entry(&task)?;
         }
@@ -763,7 +2891,7 @@ mod tests {
                 String::from("task c"),
                 TaskTime::from(start_time),
                 None,
-                false,
+                None,
             );
             db.add_task_entry(&task)?;
         }
@@ -774,7 +2902,7 @@ mod tests {
                 String::from("task d"),
                 TaskTime::from(start_time),
                 None,
-                false,
+                None,
             );
             db.add_task_entry(&task)?;
         }
@@ -802,7 +2930,7 @@ mod tests {
             String::from("task a"),
             TaskTime::from(start_time),
             Some(TaskTime::from(end_time)),
-            false,
+            None,
         );
         db.add_task_entry(&task1)?;
 
@@ -812,7 +2940,7 @@ mod tests {
             String::from("task b"),
             TaskTime::from(start_time),
             None,
-            false,
+            None,
         );
         db.add_task_entry(&task2)?;
 
@@ -822,7 +2950,7 @@ mod tests {
             String::from("task c"),
             TaskTime::from(start_time),
             None,
-            true,
+            Some(BreakCategory::new(String::from("break time"), false)),
         );
         db.add_task_entry(&task3)?;
 
@@ -832,7 +2960,7 @@ mod tests {
             String::from("task d"),
             TaskTime::from(start_time),
             None,
-            false,
+            None,
         );
         db.add_task_entry(&task4)?;
 
@@ -860,6 +2988,43 @@ mod tests {
     }
 
     #[test]
+    fn test_get_tasks_between() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db()?;
+
+        let mut tasks = Vec::new();
+        for (day, name) in [(1, "task a"), (2, "task b"), (3, "task c"), (4, "task d")] {
+            let start_time = chrono::NaiveDate::from_ymd(2021, 1, day).and_hms(10, 0, 0);
+            let task = Task::new(
+                Some(day),
+                String::from(name),
+                TaskTime::from(start_time),
+                None,
+                None,
+            );
+            db.add_task_entry(&task)?;
+            tasks.push(task);
+        }
+
+        assert_eq!(
+            db.get_tasks_between(
+                WorkDate::parse_from_str("2021-01-02")?,
+                WorkDate::parse_from_str("2021-01-03")?
+            )?,
+            TaskList::new(vec![(1, tasks[1].clone()), (1, tasks[2].clone())])
+        );
+        assert_eq!(
+            db.get_tasks_between(
+                WorkDate::parse_from_str("2021-01-05")?,
+                WorkDate::parse_from_str("2021-01-10")?
+            )?,
+            TaskList::new(vec![])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_update_task() -> Result<(), Box<dyn Error>> {
         let mut db = setup_db()?;
 
@@ -872,7 +3037,7 @@ mod tests {
             String::from("task a"),
             TaskTime::from(start_time1),
             None,
-            true,
+            Some(BreakCategory::new(String::from("break time"), false)),
         );
         db.add_task_entry(&task_pre)?;
 
@@ -881,11 +3046,12 @@ mod tests {
             String::from("task b"),
             TaskTime::from(start_time2),
             Some(TaskTime::from(end_time)),
-            false,
+            None,
         );
         db.update_task(1, &task_post1)?;
         db.conn.query_row(
-            "SELECT id, name, working_date, seq_num, start_time, end_time, is_break FROM tasks",
+            "SELECT tasks.id, task_name, working_date, tasks.seq_num, start_time, end_time, category_id IS NOT NULL \
+            FROM tasks JOIN tasknames ON tasks.taskname_id = tasknames.id",
             [],
             |row| {
                 assert_eq!(row.get::<_, u32>(0)?, 1);
@@ -911,11 +3077,12 @@ mod tests {
             String::from("task b"),
             TaskTime::from(start_time2),
             None,
-            true,
+            Some(BreakCategory::new(String::from("break time"), false)),
         );
         db.update_task(1, &task_post2)?;
         db.conn.query_row(
-            "SELECT id, name, working_date, seq_num, start_time, end_time, is_break FROM tasks",
+            "SELECT tasks.id, task_name, working_date, tasks.seq_num, start_time, end_time, category_id IS NOT NULL \
+            FROM tasks JOIN tasknames ON tasks.taskname_id = tasknames.id",
             [],
             |row| {
                 assert_eq!(row.get::<_, u32>(0)?, 1);
@@ -937,6 +3104,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_delete_task() -> Result<(), Box<dyn Error>> {
         let mut db = setup_db()?;
         assert!(db.delete_task(1).is_err());
@@ -947,7 +3115,7 @@ mod tests {
             String::from("task a"),
             TaskTime::from(start_time),
             None,
-            false,
+            None,
         );
         db.add_task_entry(&task1)?;
 
@@ -956,13 +3124,14 @@ mod tests {
             String::from("task b"),
             TaskTime::from(start_time),
             None,
-            false,
+            None,
         );
         db.add_task_entry(&task2)?;
         db.delete_task(1)?;
 
         db.conn.query_row(
-            "SELECT id, name, working_date, seq_num, start_time, end_time, is_break FROM tasks",
+            "SELECT tasks.id, task_name, working_date, tasks.seq_num, start_time, end_time, category_id IS NOT NULL \
+            FROM tasks JOIN tasknames ON tasks.taskname_id = tasknames.id",
             [],
             |row| {
                 assert_eq!(row.get::<_, u32>(0)?, 2);
@@ -982,4 +3151,19 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_delete_current_task() -> Result<(), Box<dyn Error>> {
+        let mut db = setup_db()?;
+
+        let start_time = chrono::NaiveDate::from_ymd(2021, 1, 1).and_hms(10, 50, 21);
+        let task = Task::start(String::from("task a"), TaskTime::from(start_time), None);
+        db.add_task_entry(&task)?;
+        assert_eq!(db.get_current_task_id()?, Some(1));
+
+        db.delete_task(1)?;
+        assert_eq!(db.get_current_task_id()?, None);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,135 @@
+use getset::Getters;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Number of characters of a commit hash shown for a detached HEAD.
+const SHORT_HASH_LEN: usize = 7;
+
+/// A git repository and its current branch.
+#[derive(Clone, PartialEq, Eq, Debug, Getters)]
+pub struct RepoContext {
+    #[getset(get = "pub")]
+    root: PathBuf,
+    /// Branch name, or the short commit hash if HEAD is detached.
+    #[getset(get = "pub")]
+    branch: Option<String>,
+}
+
+/// Find the git repository containing `dir` and read its current branch from `HEAD`.
+///
+/// Only local files are read, and `None` is returned outside of a repository.
+pub fn discover(dir: &Path) -> Option<RepoContext> {
+    let root = dir.ancestors().find(|d| d.join(".git").exists())?;
+    let branch = git_dir(root)
+        .and_then(|git_dir| fs::read_to_string(git_dir.join("HEAD")).ok())
+        .and_then(|head| parse_head(&head));
+
+    Some(RepoContext {
+        root: root.to_path_buf(),
+        branch,
+    })
+}
+
+/// Resolve the git directory of a working tree, following a `gitdir:` file of worktrees
+/// and submodules.
+fn git_dir(root: &Path) -> Option<PathBuf> {
+    let dot_git = root.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+
+    let content = fs::read_to_string(&dot_git).ok()?;
+    let path = PathBuf::from(content.trim().strip_prefix("gitdir:")?.trim());
+    if path.is_absolute() {
+        Some(path)
+    } else {
+        Some(root.join(path))
+    }
+}
+
+/// Parse the content of `HEAD` to a branch name, or a short commit hash if detached.
+fn parse_head(content: &str) -> Option<String> {
+    let content = content.trim();
+    if let Some(reference) = content.strip_prefix("ref:") {
+        let reference = reference.trim();
+        return Some(
+            reference
+                .strip_prefix("refs/heads/")
+                .unwrap_or(reference)
+                .to_string(),
+        );
+    }
+
+    if content.len() >= SHORT_HASH_LEN && content.chars().all(|c| c.is_ascii_hexdigit()) {
+        Some(content[..SHORT_HASH_LEN].to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::error::Error;
+
+    #[test]
+    fn test_parse_head() {
+        assert_eq!(
+            parse_head("ref: refs/heads/main\n"),
+            Some(String::from("main"))
+        );
+        assert_eq!(
+            parse_head("ref: refs/heads/feature/login\n"),
+            Some(String::from("feature/login"))
+        );
+        assert_eq!(
+            parse_head("1a2b3c4d5e6f7a8b9c0d1a2b3c4d5e6f7a8b9c0d\n"),
+            Some(String::from("1a2b3c4"))
+        );
+        assert_eq!(parse_head(""), None);
+    }
+
+    #[test]
+    fn test_discover() -> Result<(), Box<dyn Error>> {
+        let root = env::temp_dir().join(format!("tasklog-git-test-{}", std::process::id()));
+        let sub_dir = root.join("src").join("module");
+        fs::create_dir_all(&sub_dir)?;
+        fs::create_dir_all(root.join(".git"))?;
+        fs::write(root.join(".git").join("HEAD"), "ref: refs/heads/develop\n")?;
+
+        let context = discover(&sub_dir);
+
+        // a worktree refers to its git directory by a file
+        let worktree = root.join("worktree");
+        let worktree_git_dir = root.join(".git").join("worktrees").join("wt");
+        fs::create_dir_all(&worktree)?;
+        fs::create_dir_all(&worktree_git_dir)?;
+        fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", worktree_git_dir.display()),
+        )?;
+        fs::write(worktree_git_dir.join("HEAD"), "ref: refs/heads/hotfix\n")?;
+
+        let worktree_context = discover(&worktree);
+
+        fs::remove_dir_all(&root)?;
+
+        assert_eq!(
+            context,
+            Some(RepoContext {
+                root: root.clone(),
+                branch: Some(String::from("develop")),
+            })
+        );
+        assert_eq!(
+            worktree_context,
+            Some(RepoContext {
+                root: worktree,
+                branch: Some(String::from("hotfix")),
+            })
+        );
+
+        Ok(())
+    }
+}
@@ -2,6 +2,8 @@
 extern crate prettytable;
 
 pub mod db;
+pub mod git;
+pub mod matcher;
 pub mod parser;
 pub mod subcommand;
 pub mod task;
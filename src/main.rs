@@ -1,7 +1,11 @@
 use anyhow::Result;
-use clap::{crate_version, Clap};
+use clap::{crate_version, AppSettings, Clap};
 use tasklog::db::{get_db_path_from_env_var_or, Database};
+use tasklog::parser::{get_continue_gap_from_env_var_or, get_ref_pattern_from_env_var_or};
 use tasklog::subcommand;
+use tasklog::subcommand::list_log::LogOptions;
+use tasklog::subcommand::start::StartOptions;
+use tasklog::task::DEFAULT_BREAK_CATEGORY;
 
 // command line arguments
 #[derive(Clap)]
@@ -36,11 +40,47 @@ enum SubCommand {
     )]
     Unregister(UnregisterOpts),
 
+    #[clap(
+        about = "Renames a task name",
+        version = crate_version!()
+    )]
+    Rename(RenameOpts),
+
+    #[clap(
+        about = "Archives a task name",
+        version = crate_version!()
+    )]
+    Archive(ArchiveOpts),
+
+    #[clap(
+        about = "Restores an archived task name",
+        version = crate_version!()
+    )]
+    Unarchive(ArchiveOpts),
+
     #[clap(
         about = "Lists registered task names",
         version = crate_version!()
     )]
-    List,
+    List(ListOpts),
+
+    #[clap(
+        about = "Manages projects and clients of task names",
+        version = crate_version!()
+    )]
+    Project(ProjectOpts),
+
+    #[clap(
+        about = "Maps directories to task names started by `start` without a task",
+        version = crate_version!()
+    )]
+    Map(MapOpts),
+
+    #[clap(
+        about = "Manages categories of break time",
+        version = crate_version!()
+    )]
+    Category(CategoryOpts),
 
     #[clap(
         about = "Starts a task",
@@ -54,18 +94,120 @@ enum SubCommand {
     )]
     End(EndOpts),
 
+    #[clap(
+        about = "Pauses the current task to be resumed later",
+        version = crate_version!()
+    )]
+    Pause(PauseOpts),
+
+    #[clap(
+        about = "Resumes the paused task",
+        version = crate_version!()
+    )]
+    Resume(PauseOpts),
+
+    #[clap(
+        about = "Ends the break and restarts the last task before it",
+        version = crate_version!()
+    )]
+    Back(PauseOpts),
+
+    #[clap(
+        about = "Continues an ended task entry, reopening it if the gap is short",
+        version = crate_version!()
+    )]
+    Continue(ContinueOpts),
+
+    #[clap(
+        about = "Suspends the current task and starts an interruption",
+        version = crate_version!()
+    )]
+    Push(PushOpts),
+
+    #[clap(
+        about = "Ends the interruption and resumes the suspended task",
+        version = crate_version!()
+    )]
+    Pop(PauseOpts),
+
+    #[clap(
+        about = "Shows the running task and the suspended tasks",
+        version = crate_version!()
+    )]
+    Stack,
+
+    #[clap(
+        about = "Ends the current task and starts a recent task, the last one by default",
+        version = crate_version!()
+    )]
+    Switch(SwitchOpts),
+
+    #[clap(
+        about = "Lists recent task names with their numbers for `switch`",
+        version = crate_version!()
+    )]
+    Recent(RecentOpts),
+
+    #[clap(
+        about = "Shows the current task status",
+        version = crate_version!()
+    )]
+    Status(StatusOpts),
+
     #[clap(
         about = "Shows logged task entries",
         version = crate_version!()
     )]
     Log(LogOpts),
 
+    #[clap(
+        about = "Searches task entries by their names and notes",
+        version = crate_version!()
+    )]
+    Search(SearchOpts),
+
+    #[clap(
+        about = "Reports working hours per external reference, repository or branch",
+        version = crate_version!()
+    )]
+    Report(ReportOpts),
+
+    #[clap(
+        about = "Shows a heatmap of working hours per day",
+        version = crate_version!()
+    )]
+    Heatmap(HeatmapOpts),
+
+    #[clap(
+        about = "Shows a timeline of task entries",
+        version = crate_version!()
+    )]
+    Timeline(TimelineOpts),
+
+    #[clap(
+        about = "Shows intervals not covered by task entries",
+        version = crate_version!()
+    )]
+    Gaps(GapsOpts),
+
     #[clap(
         about = "Updates a task entry",
         version = crate_version!()
     )]
     Update(UpdateOpts),
 
+    #[clap(
+        about = "Splits a task entry into two at a time",
+        version = crate_version!()
+    )]
+    Split(SplitOpts),
+
+    #[clap(
+        about = "Merges task entries into one",
+        version = crate_version!()
+    )]
+    Merge(MergeOpts),
+
     #[clap(
         about = "Deletes a task entry",
         version = crate_version!()
@@ -105,25 +247,213 @@ struct UnregisterOpts {
     task_name: String,
 }
 
+#[derive(Clap)]
+struct RenameOpts {
+    #[clap(about = "Current task name")]
+    old_name: String,
+    #[clap(about = "New task name, merged if it already exists")]
+    new_name: String,
+    #[clap(short, long, about = "Keeps the current name in the past task logs")]
+    keep_history: bool,
+}
+
+#[derive(Clap)]
+struct ArchiveOpts {
+    task_name: String,
+}
+
+#[derive(Clap)]
+struct ListOpts {
+    #[clap(short, long, about = "Lists archived task names")]
+    archived: bool,
+}
+
+#[derive(Clap)]
+struct ProjectOpts {
+    #[clap(subcommand)]
+    subcmd: ProjectSubCommand,
+}
+
+#[derive(Clap)]
+enum ProjectSubCommand {
+    #[clap(about = "Adds a project or changes its client")]
+    Add(ProjectAddOpts),
+
+    #[clap(about = "Assigns a task name to a project")]
+    Assign(ProjectAssignOpts),
+
+    #[clap(about = "Lists projects")]
+    List,
+}
+
+#[derive(Clap)]
+struct ProjectAddOpts {
+    project: String,
+    #[clap(short, long, about = "Client of the project")]
+    client: Option<String>,
+}
+
+#[derive(Clap)]
+struct ProjectAssignOpts {
+    task_name: String,
+    #[clap(about = "Project name, unassigns the task name if omitted")]
+    project: Option<String>,
+}
+
+#[derive(Clap)]
+struct MapOpts {
+    #[clap(subcommand)]
+    subcmd: MapSubCommand,
+}
+
+#[derive(Clap)]
+enum MapSubCommand {
+    #[clap(about = "Maps directories matching a glob pattern to a task name")]
+    Add(MapAddOpts),
+
+    #[clap(about = "Removes a mapping of directories")]
+    Remove(MapRemoveOpts),
+
+    #[clap(about = "Lists mappings of directories")]
+    List,
+}
+
+#[derive(Clap)]
+struct MapAddOpts {
+    #[clap(about = "Glob pattern of directories, e.g. `~/work/billing` or `~/work/*/api`")]
+    pattern: String,
+    task_name: String,
+}
+
+#[derive(Clap)]
+struct MapRemoveOpts {
+    pattern: String,
+}
+
+#[derive(Clap)]
+struct CategoryOpts {
+    #[clap(subcommand)]
+    subcmd: CategorySubCommand,
+}
+
+#[derive(Clap)]
+enum CategorySubCommand {
+    #[clap(about = "Adds a break category or changes whether it counts as working time")]
+    Add(CategoryAddOpts),
+
+    #[clap(about = "Lists break categories")]
+    List,
+}
+
+#[derive(Clap)]
+struct CategoryAddOpts {
+    category: String,
+    #[clap(short, long, about = "Counts the time of the category as working time")]
+    counts_as_work: bool,
+}
+
 #[derive(Clap)]
 struct StartOpts {
-    #[clap(about = "Task number in the task name list")]
-    task_number: Option<u32>,
+    #[clap(
+        about = "Task number in the task name list, task name, or its prefix or fuzzy pattern, \
+        the task name mapped to the current directory if omitted"
+    )]
+    task: Option<String>,
+    #[clap(short, long, about = "Starts a break time", conflicts_with = "task")]
+    break_time: bool,
     #[clap(
         short,
         long,
-        about = "Starts a break time",
-        conflicts_with = "task-number"
+        about = "Starts a break time of the category",
+        conflicts_with_all = &["task", "break-time"]
     )]
-    break_time: bool,
+    category: Option<String>,
     #[clap(short, long, about = "Start time, `HHMM` format")]
     time: Option<String>,
+    #[clap(
+        short = 'g',
+        long = "tag",
+        number_of_values = 1,
+        about = "Tags the task entry, can be given multiple times"
+    )]
+    tags: Vec<String>,
+    #[clap(short = 'm', long, about = "Note of the task entry")]
+    note: Option<String>,
+    #[clap(
+        short,
+        long = "ref",
+        about = "External reference (e.g. an issue key), extracted from the name or the note \
+        by `TASKLOG_REF_PATTERN` if omitted"
+    )]
+    external_ref: Option<String>,
 }
 
 #[derive(Clap)]
 struct EndOpts {
     #[clap(short, long, about = "End time, `HHMM` format")]
     time: Option<String>,
+    #[clap(short = 'm', long, about = "Note of the task entry")]
+    note: Option<String>,
+}
+
+#[derive(Clap)]
+struct PauseOpts {
+    #[clap(short, long, about = "Time, `HHMM` format")]
+    time: Option<String>,
+}
+
+#[derive(Clap)]
+struct ContinueOpts {
+    #[clap(about = "Task number in the task list, the last ended entry if omitted")]
+    task_number: Option<u32>,
+    #[clap(short, long, about = "Time, `HHMM` format")]
+    time: Option<String>,
+}
+
+#[derive(Clap)]
+struct PushOpts {
+    #[clap(about = "Task number in the task name list, task name, or its prefix or fuzzy pattern")]
+    task: String,
+    #[clap(short, long, about = "Start time, `HHMM` format")]
+    time: Option<String>,
+    #[clap(
+        short = 'g',
+        long = "tag",
+        number_of_values = 1,
+        about = "Tags the task entry, can be given multiple times"
+    )]
+    tags: Vec<String>,
+    #[clap(short = 'm', long, about = "Note of the task entry")]
+    note: Option<String>,
+}
+
+#[derive(Clap)]
+struct SwitchOpts {
+    #[clap(about = "Task number in the recent task names, 1 if omitted")]
+    number: Option<u32>,
+    #[clap(short, long, about = "Start time, `HHMM` format")]
+    time: Option<String>,
+}
+
+#[derive(Clap)]
+struct RecentOpts {
+    #[clap(
+        short = 'n',
+        long,
+        default_value = "10",
+        about = "Maximum number of task names shown"
+    )]
+    limit: u32,
+}
+
+#[derive(Clap)]
+struct StatusOpts {
+    #[clap(
+        short,
+        long,
+        about = "Output format, placeholders: `{name}`, `{start}`, `{elapsed}`, `{total}`"
+    )]
+    format: Option<String>,
 }
 
 #[derive(Clap)]
@@ -132,16 +462,129 @@ struct LogOpts {
     all: bool,
     #[clap(short, long, about = "Date shown")]
     date: Option<String>,
+    #[clap(
+        short,
+        long,
+        conflicts_with = "by",
+        about = "Shows task durations as a bar chart in the summary"
+    )]
+    chart: bool,
+    #[clap(short = 'r', long, about = "Includes the running task in the summary")]
+    include_running: bool,
+    #[clap(
+        long,
+        possible_values = &["task", "project", "client"],
+        default_value = "task",
+        about = "Rolls up task durations in the summary"
+    )]
+    by: String,
+    #[clap(long, about = "Shows only task entries with the tag")]
+    tag: Option<String>,
+    #[clap(
+        short,
+        long,
+        about = "Shows external references and notes of task entries"
+    )]
+    verbose: bool,
+}
+
+#[derive(Clap)]
+struct SearchOpts {
+    #[clap(about = "Words to search for, each of which matches as a prefix")]
+    query: Vec<String>,
+    #[clap(
+        short = 'n',
+        long,
+        default_value = "20",
+        about = "Maximum number of entries shown"
+    )]
+    limit: u32,
+}
+
+#[derive(Clap)]
+struct ReportOpts {
+    #[clap(
+        possible_values = &["ref", "repo", "branch"],
+        about = "Key to total working hours by"
+    )]
+    target: String,
+    #[clap(short, long, about = "First date, the start of this week by default")]
+    from: Option<String>,
+    #[clap(short, long, about = "Last date, today by default")]
+    to: Option<String>,
+}
+
+#[derive(Clap)]
+struct HeatmapOpts {
+    #[clap(short, long, default_value = "12", about = "Number of weeks shown")]
+    weeks: u32,
+}
+
+#[derive(Clap)]
+struct TimelineOpts {
+    #[clap(short, long, about = "Date shown")]
+    date: Option<String>,
+}
+
+#[derive(Clap)]
+struct GapsOpts {
+    #[clap(short, long, about = "Date shown")]
+    date: Option<String>,
+    #[clap(
+        short,
+        long,
+        about = "Assigns each gap to a task or a break interactively"
+    )]
+    fill: bool,
 }
 
 #[derive(Clap)]
+#[clap(setting = AppSettings::AllowLeadingHyphen)]
 struct UpdateOpts {
     #[clap(about = "Task number in the task list")]
     task_number: u32,
-    #[clap(possible_values = &["name", "start", "end"], about = "Update target")]
+    #[clap(possible_values = &["name", "start", "end", "tags", "note", "ref"], about = "Update target")]
     target: String,
-    #[clap(about = "New value")]
-    value: String,
+    #[clap(
+        about = "New value, or tag changes like `+foo -bar` for tags, clears the note or the ref if omitted"
+    )]
+    values: Vec<String>,
+}
+
+#[derive(Clap)]
+struct SplitOpts {
+    #[clap(about = "Task number in the task list")]
+    task_number: u32,
+    #[clap(about = "Time to split at, `HHMM` format")]
+    time: String,
+    #[clap(
+        about = "Task number in the task name list, task name, or its prefix or fuzzy pattern \
+        of the second entry, which is a working task, the same task if omitted"
+    )]
+    new_task_name: Option<String>,
+}
+
+#[derive(Clap)]
+struct MergeOpts {
+    #[clap(
+        about = "Task number of the first entry in the task list",
+        required_unless_present = "auto"
+    )]
+    first: Option<u32>,
+    #[clap(
+        about = "Task number of the last entry in the task list",
+        required_unless_present = "auto"
+    )]
+    last: Option<u32>,
+    #[clap(
+        short,
+        long,
+        about = "Merges adjacent entries with the same name",
+        conflicts_with_all = &["first", "last", "force"]
+    )]
+    auto: bool,
+    #[clap(short, long, about = "Merges entries across gaps and breaks")]
+    force: bool,
 }
 
 #[derive(Clap)]
@@ -151,7 +594,8 @@ struct DeleteOpts {
 }
 
 fn main() -> Result<()> {
-    const BREAK_TIME_TASKNAME: &str = "break time";
+    const REF_PATTERN: &str = r"\b[A-Z][A-Z0-9]+-[0-9]+\b";
+    const CONTINUE_GAP_MINUTES: i64 = 15;
 
     let root_opts = Opts::parse();
     let db_path = get_db_path_from_env_var_or("tasklog.db")?;
@@ -172,35 +616,258 @@ fn main() -> Result<()> {
             subcommand::unregister::run(&mut db, &opts.task_name)?;
         }
 
-        SubCommand::List => {
+        SubCommand::Rename(opts) => {
+            let mut db = Database::connect_rw(&db_path)?;
+            subcommand::rename::run(&mut db, &opts.old_name, &opts.new_name, opts.keep_history)?;
+        }
+
+        SubCommand::Archive(opts) => {
+            let mut db = Database::connect_rw(&db_path)?;
+            subcommand::archive::archive(&mut db, &opts.task_name)?;
+        }
+
+        SubCommand::Unarchive(opts) => {
+            let mut db = Database::connect_rw(&db_path)?;
+            subcommand::archive::unarchive(&mut db, &opts.task_name)?;
+        }
+
+        SubCommand::List(opts) => {
             let db = Database::connect_r(&db_path)?;
-            subcommand::list_tasks::run(&db)?;
+            subcommand::list_tasks::run(&db, opts.archived)?;
+        }
+
+        SubCommand::Project(opts) => {
+            let mut db = Database::connect_rw(&db_path)?;
+            match opts.subcmd {
+                ProjectSubCommand::Add(opts) => {
+                    subcommand::project::add(&mut db, &opts.project, opts.client.as_deref())?
+                }
+                ProjectSubCommand::Assign(opts) => {
+                    subcommand::project::assign(&mut db, &opts.task_name, opts.project.as_deref())?
+                }
+                ProjectSubCommand::List => subcommand::project::list(&db)?,
+            }
+        }
+
+        SubCommand::Map(opts) => {
+            let mut db = Database::connect_rw(&db_path)?;
+            match opts.subcmd {
+                MapSubCommand::Add(opts) => {
+                    subcommand::map::add(&mut db, &opts.pattern, &opts.task_name)?
+                }
+                MapSubCommand::Remove(opts) => subcommand::map::remove(&mut db, &opts.pattern)?,
+                MapSubCommand::List => subcommand::map::list(&db)?,
+            }
+        }
+
+        SubCommand::Category(opts) => {
+            let mut db = Database::connect_rw(&db_path)?;
+            match opts.subcmd {
+                CategorySubCommand::Add(opts) => {
+                    subcommand::category::add(&mut db, &opts.category, opts.counts_as_work)?
+                }
+                CategorySubCommand::List => subcommand::category::list(&db)?,
+            }
         }
 
         SubCommand::Start(opts) => {
             let mut db = Database::connect_rw(&db_path)?;
             subcommand::start::run(
+                &mut db,
+                StartOptions {
+                    task: opts.task,
+                    break_category: match opts.break_time {
+                        true => Some(String::from(DEFAULT_BREAK_CATEGORY)),
+                        false => opts.category,
+                    },
+                    time: opts.time,
+                    tags: opts.tags,
+                    note: opts.note,
+                    external_ref: opts.external_ref,
+                },
+                &get_ref_pattern_from_env_var_or(REF_PATTERN)?,
+            )?;
+        }
+
+        SubCommand::End(opts) => {
+            let mut db = Database::connect_rw(&db_path)?;
+            subcommand::end::run(
+                &mut db,
+                opts.time,
+                opts.note,
+                &get_ref_pattern_from_env_var_or(REF_PATTERN)?,
+            )?;
+        }
+
+        SubCommand::Pause(opts) => {
+            let mut db = Database::connect_rw(&db_path)?;
+            subcommand::pause::pause(
+                &mut db,
+                opts.time,
+                &get_ref_pattern_from_env_var_or(REF_PATTERN)?,
+            )?;
+        }
+
+        SubCommand::Resume(opts) => {
+            let mut db = Database::connect_rw(&db_path)?;
+            subcommand::pause::resume(
+                &mut db,
+                opts.time,
+                &get_ref_pattern_from_env_var_or(REF_PATTERN)?,
+            )?;
+        }
+
+        SubCommand::Back(opts) => {
+            let mut db = Database::connect_rw(&db_path)?;
+            subcommand::back::run(
+                &mut db,
+                opts.time,
+                &get_ref_pattern_from_env_var_or(REF_PATTERN)?,
+            )?;
+        }
+
+        SubCommand::Continue(opts) => {
+            let mut db = Database::connect_rw(&db_path)?;
+            subcommand::continue_task::run(
                 &mut db,
                 opts.task_number,
-                opts.break_time,
                 opts.time,
-                BREAK_TIME_TASKNAME,
+                get_continue_gap_from_env_var_or(CONTINUE_GAP_MINUTES)?,
+                &get_ref_pattern_from_env_var_or(REF_PATTERN)?,
             )?;
         }
 
-        SubCommand::End(opts) => {
+        SubCommand::Push(opts) => {
+            let mut db = Database::connect_rw(&db_path)?;
+            subcommand::stack::push(
+                &mut db,
+                StartOptions {
+                    task: Some(opts.task),
+                    break_category: None,
+                    time: opts.time,
+                    tags: opts.tags,
+                    note: opts.note,
+                    external_ref: None,
+                },
+                &get_ref_pattern_from_env_var_or(REF_PATTERN)?,
+            )?;
+        }
+
+        SubCommand::Pop(opts) => {
+            let mut db = Database::connect_rw(&db_path)?;
+            subcommand::stack::pop(
+                &mut db,
+                opts.time,
+                &get_ref_pattern_from_env_var_or(REF_PATTERN)?,
+            )?;
+        }
+
+        SubCommand::Stack => {
+            let db = Database::connect_r(&db_path)?;
+            subcommand::stack::show(&db)?;
+        }
+
+        SubCommand::Switch(opts) => {
             let mut db = Database::connect_rw(&db_path)?;
-            subcommand::end::run(&mut db, opts.time)?;
+            subcommand::switch::switch(
+                &mut db,
+                opts.number,
+                opts.time,
+                &get_ref_pattern_from_env_var_or(REF_PATTERN)?,
+            )?;
+        }
+
+        SubCommand::Recent(opts) => {
+            let db = Database::connect_r(&db_path)?;
+            subcommand::switch::recent(&db, opts.limit)?;
+        }
+
+        SubCommand::Status(opts) => {
+            let db = Database::connect_r(&db_path)?;
+            if !subcommand::status::run(&db, opts.format)? {
+                std::process::exit(1);
+            }
         }
 
         SubCommand::Log(opts) => {
             let db = Database::connect_rw(&db_path)?;
-            subcommand::list_log::run(&db, opts.all, opts.date)?;
+            subcommand::list_log::run(
+                &db,
+                LogOptions {
+                    show_all: opts.all,
+                    date: opts.date,
+                    chart: opts.chart,
+                    include_running: opts.include_running,
+                    group_by: opts.by,
+                    tag: opts.tag,
+                    verbose: opts.verbose,
+                },
+            )?;
+        }
+
+        SubCommand::Search(opts) => {
+            let db = Database::connect_r(&db_path)?;
+            subcommand::search::run(&db, &opts.query.join(" "), opts.limit)?;
+        }
+
+        SubCommand::Report(opts) => {
+            let db = Database::connect_r(&db_path)?;
+            subcommand::report::run(&db, &opts.target, opts.from, opts.to)?;
+        }
+
+        SubCommand::Heatmap(opts) => {
+            let db = Database::connect_r(&db_path)?;
+            subcommand::heatmap::run(&db, opts.weeks)?;
+        }
+
+        SubCommand::Timeline(opts) => {
+            let db = Database::connect_r(&db_path)?;
+            subcommand::timeline::run(&db, opts.date)?;
+        }
+
+        SubCommand::Gaps(opts) => {
+            if opts.fill {
+                let mut db = Database::connect_rw(&db_path)?;
+                subcommand::gaps::fill(
+                    &mut db,
+                    opts.date,
+                    &get_ref_pattern_from_env_var_or(REF_PATTERN)?,
+                )?;
+            } else {
+                let db = Database::connect_r(&db_path)?;
+                subcommand::gaps::run(&db, opts.date)?;
+            }
         }
 
         SubCommand::Update(opts) => {
             let db = Database::connect_rw(&db_path)?;
-            subcommand::update::run(&db, opts.task_number, opts.target, opts.value)?;
+            subcommand::update::run(
+                &db,
+                opts.task_number,
+                opts.target,
+                opts.values,
+                &get_ref_pattern_from_env_var_or(REF_PATTERN)?,
+            )?;
+        }
+
+        SubCommand::Split(opts) => {
+            let mut db = Database::connect_rw(&db_path)?;
+            subcommand::split::run(
+                &mut db,
+                opts.task_number,
+                opts.time,
+                opts.new_task_name,
+                &get_ref_pattern_from_env_var_or(REF_PATTERN)?,
+            )?;
+        }
+
+        SubCommand::Merge(opts) => {
+            let mut db = Database::connect_rw(&db_path)?;
+            if opts.auto {
+                subcommand::merge::auto(&mut db)?;
+            } else if let (Some(first), Some(last)) = (opts.first, opts.last) {
+                subcommand::merge::run(&mut db, first, last, opts.force)?;
+            }
         }
 
         SubCommand::Delete(opts) => {
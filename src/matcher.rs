@@ -0,0 +1,221 @@
+use std::path::Path;
+
+/// Find task names matching a pattern.
+///
+/// An exact match takes precedence over prefix matches, and prefix matches take precedence
+/// over fuzzy matches, in which the characters of the pattern appear in the name in order.
+/// Prefix and fuzzy matches ignore case. Fuzzy matches are ordered from the closest one.
+pub fn find_tasknames(pattern: &str, names: &[String]) -> Vec<String> {
+    if let Some(name) = names.iter().find(|name| name.as_str() == pattern) {
+        return vec![name.clone()];
+    }
+
+    let pattern_lower = pattern.to_lowercase();
+
+    let prefix_matches = names
+        .iter()
+        .filter(|name| name.to_lowercase().starts_with(&pattern_lower))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !prefix_matches.is_empty() {
+        return prefix_matches;
+    }
+
+    let mut fuzzy_matches = names
+        .iter()
+        .filter_map(|name| fuzzy_score(&pattern_lower, &name.to_lowercase()).map(|s| (s, name)))
+        .collect::<Vec<_>>();
+    fuzzy_matches.sort();
+
+    fuzzy_matches
+        .into_iter()
+        .map(|(_, name)| name.clone())
+        .collect()
+}
+
+/// Score a fuzzy match, lower is closer. Returns `None` if the name does not match.
+///
+/// The score is the length of the shortest part of the name which contains the characters
+/// of the pattern in order.
+fn fuzzy_score(pattern: &str, name: &str) -> Option<usize> {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+
+    if pattern.is_empty() {
+        return Some(0);
+    }
+
+    (0..name.len())
+        .filter(|&start| name[start] == pattern[0])
+        .filter_map(|start| {
+            let mut rest = pattern.iter().skip(1).peekable();
+            for (i, c) in name.iter().enumerate().skip(start + 1) {
+                match rest.peek() {
+                    Some(&p) if p == c => {
+                        rest.next();
+                    }
+                    Some(_) => {}
+                    None => return Some(i - start),
+                }
+            }
+            match rest.peek() {
+                Some(_) => None,
+                None => Some(name.len() - start),
+            }
+        })
+        .min()
+}
+
+/// Check whether a path matches a glob pattern.
+///
+/// Paths are compared by `/`-separated components, in which `*` matches any characters and `?`
+/// matches a character. A `**` component matches any number of components.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.split('/').collect::<Vec<_>>();
+    let path = path.split('/').collect::<Vec<_>>();
+
+    match_components(&pattern, &path)
+}
+
+/// Find the task name mapped to a directory by `(pattern, task name)` pairs.
+///
+/// A pattern matches the directory or any of its ancestors, and the longest pattern is taken
+/// if several ones match.
+pub fn find_mapped_taskname<'a>(dir: &Path, mappings: &'a [(String, String)]) -> Option<&'a str> {
+    mappings
+        .iter()
+        .filter(|(pattern, _)| {
+            dir.ancestors()
+                .any(|d| glob_match(pattern, &d.to_string_lossy()))
+        })
+        .max_by_key(|(pattern, _)| pattern.len())
+        .map(|(_, name)| name.as_str())
+}
+
+/// Match path components with pattern components.
+fn match_components(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|i| match_components(rest, &path[i..])),
+        Some((first, rest)) => match path.split_first() {
+            Some((component, path_rest)) => {
+                let first = first.chars().collect::<Vec<_>>();
+                let component = component.chars().collect::<Vec<_>>();
+                match_wildcard(&first, &component) && match_components(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Match a component with a pattern which contains `*` and `?`.
+fn match_wildcard(pattern: &[char], text: &[char]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some(('*', rest)) => (0..=text.len()).any(|i| match_wildcard(rest, &text[i..])),
+        Some((p, rest)) => match text.split_first() {
+            Some((c, text_rest)) => (*p == '?' || p == c) && match_wildcard(rest, text_rest),
+            None => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> Vec<String> {
+        vec![
+            String::from("code review"),
+            String::from("coding"),
+            String::from("meeting"),
+            String::from("Meeting notes"),
+        ]
+    }
+
+    #[test]
+    fn test_find_tasknames_exact() {
+        assert_eq!(
+            find_tasknames("meeting", &names()),
+            vec![String::from("meeting")]
+        );
+    }
+
+    #[test]
+    fn test_find_tasknames_prefix() {
+        assert_eq!(
+            find_tasknames("cod", &names()),
+            vec![String::from("code review"), String::from("coding")]
+        );
+        assert_eq!(
+            find_tasknames("meeting n", &names()),
+            vec![String::from("Meeting notes")]
+        );
+    }
+
+    #[test]
+    fn test_find_tasknames_fuzzy() {
+        assert_eq!(
+            find_tasknames("crv", &names()),
+            vec![String::from("code review")]
+        );
+        assert_eq!(
+            find_tasknames("mtg", &names()),
+            vec![String::from("Meeting notes"), String::from("meeting")]
+        );
+        assert_eq!(
+            find_tasknames("ing", &names()),
+            vec![
+                String::from("Meeting notes"),
+                String::from("coding"),
+                String::from("meeting")
+            ]
+        );
+        assert!(find_tasknames("xyz", &names()).is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("abc", "abc"), Some(3));
+        assert_eq!(fuzzy_score("ac", "abc"), Some(3));
+        assert_eq!(fuzzy_score("ac", "a-ab-c"), Some(4));
+        assert_eq!(fuzzy_score("ca", "abc"), None);
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("/home/me/work/billing", "/home/me/work/billing"));
+        assert!(!glob_match(
+            "/home/me/work/billing",
+            "/home/me/work/billing/src"
+        ));
+        assert!(glob_match("/home/me/work/bill*", "/home/me/work/billing"));
+        assert!(glob_match("/home/me/work/billin?", "/home/me/work/billing"));
+        assert!(!glob_match("/home/me/work/*", "/home/me/work/billing/src"));
+        assert!(glob_match("/home/me/**/billing", "/home/me/work/billing"));
+        assert!(glob_match("/home/me/**/billing", "/home/me/billing"));
+        assert!(glob_match(
+            "/home/me/work/billing/**",
+            "/home/me/work/billing/src/db"
+        ));
+        assert!(!glob_match("/home/me/**/billing", "/home/me/work/payroll"));
+    }
+
+    #[test]
+    fn test_find_mapped_taskname() {
+        let mappings = vec![
+            (String::from("/work/*"), String::from("misc")),
+            (String::from("/work/billing"), String::from("billing")),
+        ];
+
+        assert_eq!(
+            find_mapped_taskname(Path::new("/work/billing/src"), &mappings),
+            Some("billing")
+        );
+        assert_eq!(
+            find_mapped_taskname(Path::new("/work/payroll"), &mappings),
+            Some("misc")
+        );
+        assert_eq!(find_mapped_taskname(Path::new("/home"), &mappings), None);
+    }
+}
@@ -1,5 +1,7 @@
 use anyhow::{anyhow, Result};
+use chrono::Duration;
 use regex::Regex;
+use std::env;
 
 /// Parse an `"HHMM"` or `"HH:MM"` style string to a tuple of int values which replesents
 /// hours and minutes.
@@ -17,6 +19,7 @@ pub fn parse_time_hm(s: &str) -> Result<(u32, u32)> {
 }
 
 /// Parse a date string to a tuple of int values which replesents year, month, and day.
+#[allow(clippy::manual_range_contains)]
 pub fn parse_date(s: &str) -> Result<(i32, u32, u32)> {
     let re_ymd = Regex::new(r"(?P<year>[0-9]{4})-?(?P<month>[0-9]{2})-?(?P<day>[0-9]{2})").unwrap();
 
@@ -32,6 +35,73 @@ pub fn parse_date(s: &str) -> Result<(i32, u32, u32)> {
     }
 }
 
+/// Parse a tag, a leading `#` is omitted.
+pub fn parse_tag(s: &str) -> Result<String> {
+    let tag = s.strip_prefix('#').unwrap_or(s);
+
+    if tag.is_empty() || tag.contains(|c: char| c.is_whitespace() || c == ',') {
+        Err(anyhow!("invalid tag: {}", s))
+    } else {
+        Ok(tag.to_string())
+    }
+}
+
+/// Parse tag changes like `+foo -bar` to a pair of tags to be added and removed.
+///
+/// Tags without `+` or `-` are added.
+pub fn parse_tag_changes(changes: &[String]) -> Result<(Vec<String>, Vec<String>)> {
+    let mut add = Vec::new();
+    let mut remove = Vec::new();
+
+    for change in changes {
+        if let Some(tag) = change.strip_prefix('-') {
+            remove.push(parse_tag(tag)?);
+        } else {
+            add.push(parse_tag(change.strip_prefix('+').unwrap_or(change))?);
+        }
+    }
+
+    Ok((add, remove))
+}
+
+/// Get the pattern of external references from the environment variable
+/// `TASKLOG_REF_PATTERN`, or the default pattern.
+pub fn get_ref_pattern_from_env_var_or(default: &str) -> Result<Regex> {
+    let pattern = env::var("TASKLOG_REF_PATTERN").unwrap_or_else(|_| String::from(default));
+
+    Regex::new(&pattern).map_err(|e| anyhow!("invalid reference pattern: {}", e))
+}
+
+/// Get the maximum gap in minutes to continue an ended task entry from the environment
+/// variable `TASKLOG_CONTINUE_GAP`, or the default value.
+pub fn get_continue_gap_from_env_var_or(default: i64) -> Result<Duration> {
+    let minutes = match env::var("TASKLOG_CONTINUE_GAP") {
+        Ok(s) => s
+            .parse::<i64>()
+            .map_err(|e| anyhow!("invalid continue gap: {}", e))?,
+        Err(_) => default,
+    };
+    if minutes < 0 {
+        return Err(anyhow!("invalid continue gap: must not be negative"));
+    }
+
+    Ok(Duration::minutes(minutes))
+}
+
+/// Replace `{key}` placeholders in the format string with the values in a single scan, so
+/// placeholders in the values are kept as is. Unknown placeholders are left unchanged.
+pub fn fill_placeholders(format: &str, values: &[(&str, String)]) -> String {
+    let re = Regex::new(r"\{(\w+)\}").unwrap();
+    re.replace_all(format, |caps: &regex::Captures| {
+        let key = caps.get(1).unwrap().as_str();
+        match values.iter().find(|(k, _)| *k == key) {
+            Some((_, value)) => value.clone(),
+            None => caps.get(0).unwrap().as_str().to_string(),
+        }
+    })
+    .to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,4 +135,93 @@ mod tests {
         assert!(parse_date("20210100").is_err());
         assert!(parse_date("20211232").is_err());
     }
+
+    #[test]
+    fn test_parse_tag() {
+        assert_eq!(parse_tag("review").unwrap(), "review");
+        assert_eq!(parse_tag("#review").unwrap(), "review");
+
+        assert!(parse_tag("").is_err());
+        assert!(parse_tag("#").is_err());
+        assert!(parse_tag("code review").is_err());
+        assert!(parse_tag("a,b").is_err());
+    }
+
+    #[test]
+    fn test_parse_tag_changes() {
+        let changes = vec![
+            String::from("+foo"),
+            String::from("-bar"),
+            String::from("baz"),
+        ];
+        assert_eq!(
+            parse_tag_changes(&changes).unwrap(),
+            (
+                vec![String::from("foo"), String::from("baz")],
+                vec![String::from("bar")]
+            )
+        );
+
+        assert!(parse_tag_changes(&[String::from("-")]).is_err());
+    }
+
+    #[test]
+    fn test_get_ref_pattern_from_env_var_or() {
+        env::remove_var("TASKLOG_REF_PATTERN");
+        let pattern = get_ref_pattern_from_env_var_or(r"\bPROJ-[0-9]+\b").unwrap();
+        assert!(pattern.is_match("PROJ-12 review"));
+
+        env::set_var("TASKLOG_REF_PATTERN", r"gh-([0-9]+)");
+        let pattern = get_ref_pattern_from_env_var_or(r"\bPROJ-[0-9]+\b").unwrap();
+        assert!(pattern.is_match("fix gh-42"));
+
+        env::set_var("TASKLOG_REF_PATTERN", "(");
+        assert!(get_ref_pattern_from_env_var_or(r"\bPROJ-[0-9]+\b").is_err());
+
+        env::remove_var("TASKLOG_REF_PATTERN");
+    }
+
+    #[test]
+    fn test_get_continue_gap_from_env_var_or() {
+        env::remove_var("TASKLOG_CONTINUE_GAP");
+        assert_eq!(
+            get_continue_gap_from_env_var_or(15).unwrap(),
+            Duration::minutes(15)
+        );
+
+        env::set_var("TASKLOG_CONTINUE_GAP", "0");
+        assert_eq!(
+            get_continue_gap_from_env_var_or(15).unwrap(),
+            Duration::minutes(0)
+        );
+
+        env::set_var("TASKLOG_CONTINUE_GAP", "-5");
+        assert!(get_continue_gap_from_env_var_or(15).is_err());
+
+        env::set_var("TASKLOG_CONTINUE_GAP", "ten");
+        assert!(get_continue_gap_from_env_var_or(15).is_err());
+
+        env::remove_var("TASKLOG_CONTINUE_GAP");
+    }
+
+    #[test]
+    fn test_fill_placeholders() {
+        let values = [
+            ("name", String::from("fix {elapsed} display")),
+            ("elapsed", String::from("00:15")),
+        ];
+        assert_eq!(
+            fill_placeholders("{name}: {elapsed} {unknown}", &values),
+            "fix {elapsed} display: 00:15 {unknown}"
+        );
+
+        let values = [
+            ("elapsed", String::from("00:15")),
+            ("name", String::from("show {name}")),
+        ];
+        assert_eq!(
+            fill_placeholders("{name} {elapsed}", &values),
+            "show {name} 00:15"
+        );
+    }
 }
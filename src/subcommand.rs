@@ -1,10 +1,28 @@
+pub mod archive;
+pub mod back;
+pub mod category;
+pub mod continue_task;
 pub mod delete;
 pub mod end;
+pub mod gaps;
+pub mod heatmap;
 pub mod init;
 pub mod list_log;
 pub mod list_tasks;
 pub mod manager;
+pub mod map;
+pub mod merge;
+pub mod pause;
+pub mod project;
 pub mod register;
+pub mod rename;
+pub mod report;
+pub mod search;
+pub mod split;
+pub mod stack;
 pub mod start;
+pub mod status;
+pub mod switch;
+pub mod timeline;
 pub mod unregister;
 pub mod update;
@@ -0,0 +1,20 @@
+use crate::db::Database;
+use anyhow::Result;
+
+/// Archive a task name.
+pub fn archive(db: &mut Database, task_name: &str) -> Result<()> {
+    if let Err(e) = db.set_taskname_archived(task_name, true) {
+        eprintln!("{}: {}", e, task_name);
+    }
+
+    Ok(())
+}
+
+/// Restore an archived task name.
+pub fn unarchive(db: &mut Database, task_name: &str) -> Result<()> {
+    if let Err(e) = db.set_taskname_archived(task_name, false) {
+        eprintln!("{}: {}", e, task_name);
+    }
+
+    Ok(())
+}
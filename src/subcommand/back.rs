@@ -0,0 +1,42 @@
+use crate::db::Database;
+use crate::subcommand::end::end_task;
+use crate::task::{TaskTime, TimeDisplay, WorkDate};
+use anyhow::{anyhow, Result};
+use regex::Regex;
+
+/// End the running break and start a new entry of the last task before it in the working
+/// date, with its tags and note.
+pub fn run(db: &mut Database, time: Option<String>, ref_pattern: &Regex) -> Result<()> {
+    let start_time = match time {
+        Some(t) => TaskTime::parse_from_str_hhmm(&t)?,
+        None => TaskTime::now(),
+    };
+
+    let current_task_id = db.get_current_task_id()?;
+    if let Some(task_id) = current_task_id {
+        if !db.get_task(task_id)?.is_break_time() {
+            return Err(anyhow!("break time is not running"));
+        }
+    }
+
+    let tasks = db.get_tasks(false, Some(WorkDate::from(start_time)))?;
+    let last_task = tasks
+        .last_non_break_task()
+        .ok_or(anyhow!("no task to go back to"))?;
+    let new_task = last_task.restart(start_time);
+
+    // end the break
+    if let Some(task_id) = current_task_id {
+        end_task(db, task_id, &start_time, None, ref_pattern)?;
+    }
+
+    db.add_task_entry(&new_task)?;
+
+    println!(
+        "{} started at {}",
+        new_task.name(),
+        new_task.start_time().to_string_hhmm()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::db::Database;
+use anyhow::Result;
+use prettytable::{format, Table};
+
+/// Add a break category, or change whether an existing category counts as working time.
+pub fn add(db: &mut Database, category: &str, counts_as_work: bool) -> Result<()> {
+    db.add_break_category(category, counts_as_work)
+}
+
+/// Print break categories as a table format.
+pub fn list(db: &Database) -> Result<()> {
+    let categories = db.get_break_categories()?;
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+
+    // title
+    table.add_row(row![bl -> "Category", bl -> "Counts as work"]);
+
+    // contents
+    for category in categories {
+        let counts_as_work = if *category.counts_as_work() {
+            "yes"
+        } else {
+            "no"
+        };
+        table.add_row(row![l -> category.name(), l -> counts_as_work]);
+    }
+
+    table.printstd();
+
+    Ok(())
+}
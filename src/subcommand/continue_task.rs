@@ -0,0 +1,57 @@
+use crate::db::Database;
+use crate::subcommand::start::start_task;
+use crate::task::{TaskTime, TimeDisplay, WorkDate};
+use anyhow::{anyhow, Result};
+use chrono::Duration;
+use regex::Regex;
+
+/// Continue a task entry, the last ended one if not given.
+///
+/// The entry is reopened if it is the last entry, no task is running, and it ended less than
+/// `max_gap` ago. Otherwise a new entry of the task is started.
+pub fn run(
+    db: &mut Database,
+    task_number: Option<u32>,
+    time: Option<String>,
+    max_gap: Duration,
+    ref_pattern: &Regex,
+) -> Result<()> {
+    let start_time = match time {
+        Some(t) => TaskTime::parse_from_str_hhmm(&t)?,
+        None => TaskTime::now(),
+    };
+
+    let working_date = WorkDate::from(start_time);
+    let tasks = db
+        .get_tasks(false, Some(working_date))?
+        .into_iter()
+        .map(|(_, task)| task)
+        .collect::<Vec<_>>();
+    let task = match task_number {
+        Some(n) => db.get_task(db.get_task_id_by_seqnum(n, working_date)?)?,
+        None => tasks
+            .iter()
+            .filter(|task| task.end_time().is_some())
+            .max_by_key(|task| *task.start_time())
+            .cloned()
+            .ok_or(anyhow!("no task to continue"))?,
+    };
+    let is_reopened = task.is_reopened_at(start_time, max_gap)?;
+
+    let is_last = tasks
+        .iter()
+        .all(|other| other.start_time() <= task.start_time());
+    let is_running = db.get_current_task_id()?.is_some();
+
+    if is_last && !is_running && is_reopened {
+        db.reopen_task(task.id().unwrap())?;
+        println!(
+            "{} continued at {}",
+            task.name(),
+            start_time.to_string_hhmm()
+        );
+        Ok(())
+    } else {
+        start_task(db, task.restart(start_time), ref_pattern)
+    }
+}
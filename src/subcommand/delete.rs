@@ -3,6 +3,7 @@ use crate::task::{TimeDisplay, WorkDate};
 use anyhow::Result;
 use dialoguer::Confirm;
 
+#[allow(clippy::to_string_in_format_args)]
 pub fn run(db: &mut Database, task_number: u32) -> Result<()> {
     let working_date = WorkDate::now();
 
@@ -1,8 +1,14 @@
 use crate::db::Database;
 use crate::task::{Task, TaskTime, TimeDisplay};
 use anyhow::Result;
+use regex::Regex;
 
-pub fn run(db: &mut Database, time: Option<String>) -> Result<()> {
+pub fn run(
+    db: &mut Database,
+    time: Option<String>,
+    note: Option<String>,
+    ref_pattern: &Regex,
+) -> Result<()> {
     // build end time
     let end_time = match time {
         Some(t) => TaskTime::parse_from_str_hhmm(&t)?,
@@ -11,15 +17,22 @@ pub fn run(db: &mut Database, time: Option<String>) -> Result<()> {
 
     // fill end time of the current task
     if let Some(current_task_id) = db.get_current_task_id()? {
-        end_task(db, current_task_id, &end_time)?;
+        end_task(db, current_task_id, &end_time, note, ref_pattern)?;
     }
 
     Ok(())
 }
 
-/// End current task.
-pub fn end_task(db: &mut Database, task_id: u32, end_time: &TaskTime) -> Result<()> {
-    let updated_task = fill_end_time(db, task_id, &end_time)?;
+/// End current task, and set its note if given.
+#[allow(clippy::needless_borrow)]
+pub fn end_task(
+    db: &mut Database,
+    task_id: u32,
+    end_time: &TaskTime,
+    note: Option<String>,
+    ref_pattern: &Regex,
+) -> Result<()> {
+    let updated_task = fill_end_time(db, task_id, &end_time, note, ref_pattern)?;
     db.reset_manager()?;
 
     println!(
@@ -32,9 +45,19 @@ pub fn end_task(db: &mut Database, task_id: u32, end_time: &TaskTime) -> Result<
 }
 
 /// Fill the end time.
-fn fill_end_time(db: &mut Database, task_id: u32, end_time: &TaskTime) -> Result<Task> {
+fn fill_end_time(
+    db: &mut Database,
+    task_id: u32,
+    end_time: &TaskTime,
+    note: Option<String>,
+    ref_pattern: &Regex,
+) -> Result<Task> {
     let mut task = db.get_task(task_id)?;
     task.set_end_time(Some(*end_time));
+    let task = match note {
+        Some(_) => task.change_note(note, ref_pattern),
+        None => task.fill_external_ref(ref_pattern),
+    };
     db.update_task(task_id, &task)?;
 
     Ok(task)
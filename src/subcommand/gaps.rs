@@ -0,0 +1,117 @@
+use crate::db::Database;
+use crate::subcommand::list_log::build_date;
+use crate::task::{Task, TaskTime, TimeDisplay, WorkDate};
+use anyhow::{anyhow, Result};
+use console::user_attended_stderr;
+use dialoguer::Select;
+use prettytable::{format, Table};
+use regex::Regex;
+
+/// Print intervals not covered by task entries on a day.
+pub fn run(db: &Database, date: Option<String>) -> Result<()> {
+    find_gaps(db, date)?;
+
+    Ok(())
+}
+
+/// Print intervals not covered by task entries on a day, and let the user assign each of
+/// them to a task or a break.
+pub fn fill(db: &mut Database, date: Option<String>, ref_pattern: &Regex) -> Result<()> {
+    let gaps = find_gaps(db, date)?;
+    fill_gaps(db, &gaps, ref_pattern)
+}
+
+/// Find and print gaps on a day.
+fn find_gaps(db: &Database, date: Option<String>) -> Result<Vec<(TaskTime, TaskTime)>> {
+    let date = build_date(date, WorkDate::now())?;
+    let gaps = db.get_tasks(false, Some(date))?.gaps();
+
+    if gaps.is_empty() {
+        println!("no gaps");
+    } else {
+        print_gaps(&gaps);
+    }
+
+    Ok(gaps)
+}
+
+/// Print gaps as a table format.
+fn print_gaps(gaps: &[(TaskTime, TaskTime)]) {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+
+    // title
+    table.add_row(row![bl -> "Start", bl -> "End", br -> "Duration"]);
+
+    // contents
+    for (start, end) in gaps {
+        let duration = (*end - *start).to_string_hhmm();
+        table.add_row(row![l -> start.to_string_hhmm(), l -> end.to_string_hhmm(), r -> duration]);
+    }
+
+    table.printstd();
+}
+
+/// Let the user pick a task name or a break category for each gap, and add task entries.
+fn fill_gaps(db: &mut Database, gaps: &[(TaskTime, TaskTime)], ref_pattern: &Regex) -> Result<()> {
+    if !user_attended_stderr() {
+        return Err(anyhow!("gaps can be filled only interactively"));
+    }
+
+    let tasknames = db
+        .get_registered_tasknames()?
+        .into_iter()
+        .map(|(_, name)| name)
+        .collect::<Vec<_>>();
+    let categories = db.get_break_categories()?;
+
+    let mut items = vec![String::from("(skip)")];
+    items.extend(tasknames.iter().cloned());
+    items.extend(
+        categories
+            .iter()
+            .map(|category| format!("{} (break)", category.name())),
+    );
+
+    for (start, end) in gaps {
+        let selection = Select::new()
+            .with_prompt(format!(
+                "Assign {} - {}",
+                start.to_string_hhmm(),
+                end.to_string_hhmm()
+            ))
+            .items(&items)
+            .default(0)
+            .interact_opt()?;
+
+        // items are the skip, task names and break categories in order
+        let task = match selection {
+            None => break,
+            Some(0) => continue,
+            Some(i) if i <= tasknames.len() => {
+                Task::new(None, tasknames[i - 1].clone(), *start, Some(*end), None)
+                    .fill_external_ref(ref_pattern)
+            }
+            Some(i) => {
+                let category = categories[i - 1 - tasknames.len()].clone();
+                Task::new(
+                    None,
+                    category.name().clone(),
+                    *start,
+                    Some(*end),
+                    Some(category),
+                )
+            }
+        };
+        db.insert_task_entry(&task)?;
+
+        println!(
+            "{} added at {} - {}",
+            task.name(),
+            start.to_string_hhmm(),
+            end.to_string_hhmm()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use crate::db::Database;
+use crate::task::{TimeDisplay, WorkDate};
+use anyhow::{anyhow, Result};
+use chrono::Duration;
+use std::io::Write;
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+
+/// Upper bounds of the heat levels in hours, durations beyond the last one are overwork.
+const LEVEL_BOUNDS: [i64; 4] = [2, 4, 6, 8];
+
+/// Cell patterns for each heat level.
+const LEVEL_CELLS: [&str; 6] = [" · ", "░░░", "▒▒▒", "▓▓▓", "███", "███"];
+
+/// Print a heatmap of working hours per day for the last weeks.
+pub fn run(db: &Database, weeks: u32) -> Result<()> {
+    if weeks == 0 {
+        return Err(anyhow!("number of weeks must be positive"));
+    }
+
+    let today = WorkDate::now();
+    let first = today.week_start().add_days(-7 * (weeks as i64 - 1));
+    let durations = db
+        .get_tasks_between(first, today)?
+        .working_duration_by_date();
+
+    let mut stdout = StandardStream::stdout(ColorChoice::Auto);
+
+    // header
+    write!(&mut stdout, "{:<10}", "Week")?;
+    for day in &["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"] {
+        write!(&mut stdout, " {}", day)?;
+    }
+    writeln!(&mut stdout, "  {:>8}", "Total")?;
+
+    // one row per week with its total in the margin
+    for week in 0..weeks as i64 {
+        let monday = first.add_days(7 * week);
+        let mut total = Duration::seconds(0);
+
+        write!(&mut stdout, "{:<10}", monday.to_string())?;
+        for day in 0..7 {
+            let date = monday.add_days(day);
+            write!(&mut stdout, " ")?;
+            if date > today {
+                write!(&mut stdout, "   ")?;
+            } else {
+                let duration = durations
+                    .get(&date)
+                    .copied()
+                    .unwrap_or_else(|| Duration::seconds(0));
+                total = total + duration;
+                write_cell(&mut stdout, level(&duration))?;
+            }
+        }
+        writeln!(&mut stdout, "  {:>8}", total.to_string_hhmm())?;
+    }
+
+    // legend
+    writeln!(&mut stdout)?;
+    write!(&mut stdout, "{:<10}", "")?;
+    for level in 0..LEVEL_CELLS.len() {
+        write!(&mut stdout, " ")?;
+        write_cell(&mut stdout, level)?;
+        let label = match level {
+            0 => String::from("none"),
+            n if n <= LEVEL_BOUNDS.len() => format!("<{}h", LEVEL_BOUNDS[n - 1]),
+            _ => format!("{}h+", LEVEL_BOUNDS[LEVEL_BOUNDS.len() - 1]),
+        };
+        write!(&mut stdout, " {}", label)?;
+    }
+    writeln!(&mut stdout)?;
+
+    Ok(())
+}
+
+/// Classify a duration into a heat level.
+fn level(duration: &Duration) -> usize {
+    if *duration <= Duration::seconds(0) {
+        return 0;
+    }
+
+    LEVEL_BOUNDS
+        .iter()
+        .position(|&hours| *duration < Duration::hours(hours))
+        .map_or(LEVEL_CELLS.len() - 1, |i| i + 1)
+}
+
+/// Write a cell of the heatmap, overwork is highlighted in red.
+fn write_cell(out: &mut StandardStream, level: usize) -> Result<()> {
+    let mut spec = ColorSpec::new();
+    match level {
+        0 => spec.set_dimmed(true),
+        n if n == LEVEL_CELLS.len() - 1 => spec.set_fg(Some(Color::Red)).set_bold(true),
+        _ => spec.set_fg(Some(Color::Green)),
+    };
+
+    out.set_color(&spec)?;
+    write!(out, "{}", LEVEL_CELLS[level])?;
+    out.reset()?;
+
+    Ok(())
+}
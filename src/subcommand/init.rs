@@ -13,6 +13,9 @@ fn initialize_db(db: &mut Database, force: bool) -> Result<()> {
     if !db.is_ready()? || force {
         db.initialize()?;
         println!("Database created: {}", db.location());
+    } else if db.is_outdated()? {
+        db.migrate()?;
+        println!("Database migrated: {}", db.location());
     } else {
         println!(
             "Database already exists: {}\n\
@@ -1,21 +1,65 @@
-use crate::db::Database;
-use crate::task::{TaskList, TaskSummary, TimeDisplay, WorkDate};
+use crate::db::{Database, ProjectMap};
+use crate::parser::parse_tag;
+use crate::task::{TaskList, TaskSummary, TaskTime, TimeDisplay, WorkDate};
 use anyhow::Result;
-use prettytable::{format, table, Row, Table};
+use chrono::Duration;
+use prettytable::{format, table, Cell, Row, Table};
+
+/// Maximum number of characters of a bar in the task duration chart.
+const CHART_WIDTH: usize = 30;
+
+/// Label for task names without a project or a client.
+const UNASSIGNED: &str = "(none)";
+
+/// Options of the task log.
+pub struct LogOptions {
+    /// Lists all task logs instead of the date.
+    pub show_all: bool,
+    /// Date shown, today if not given.
+    pub date: Option<String>,
+    /// Shows task durations as a bar chart in the summary.
+    pub chart: bool,
+    /// Includes the running task in the summary.
+    pub include_running: bool,
+    /// Rolls up task durations by `task`, `project` or `client` in the summary.
+    pub group_by: String,
+    /// Shows only task entries with the tag.
+    pub tag: Option<String>,
+    /// Shows external references and notes of task entries.
+    pub verbose: bool,
+}
 
 /// Print task log
-pub fn run(db: &Database, show_all: bool, date: Option<String>) -> Result<()> {
-    let date = build_date(date, WorkDate::now())?;
-    let tasks = db.get_tasks(show_all, Some(date))?;
+#[allow(clippy::print_with_newline)]
+pub fn run(db: &Database, opts: LogOptions) -> Result<()> {
+    let date = build_date(opts.date, WorkDate::now())?;
+    let tasks = db.get_tasks(opts.show_all, Some(date))?;
+    let tasks = match opts.tag {
+        Some(tag) => tasks.filter_by_tag(parse_tag(&tag)?.as_str()),
+        None => tasks,
+    };
+
+    // the running task is shown with the elapsed time up to now
+    let running_id = db.get_current_task_id()?;
+    let tasks_with_running = match running_id {
+        Some(id) => tasks.end_running_at(id, TaskTime::now()),
+        None => tasks.clone(),
+    };
 
     // show list
-    print_list(tasks.clone())?;
+    print_list(tasks_with_running.clone(), running_id, opts.verbose)?;
 
     // show summary
-    if !show_all {
-        if let Some(task_summary) = tasks.summary() {
+    if !opts.show_all {
+        let summary = if opts.include_running {
+            tasks_with_running.summary()
+        } else {
+            tasks.summary()
+        };
+        if let Some(task_summary) = summary {
+            let projects = db.get_taskname_projects()?;
             print!("\n");
-            print_summary(task_summary)?;
+            print_summary(task_summary, opts.chart, &opts.group_by, &projects)?;
         }
     }
 
@@ -23,7 +67,7 @@ pub fn run(db: &Database, show_all: bool, date: Option<String>) -> Result<()> {
 }
 
 /// Build `WorkDate` form an input string.
-fn build_date(date: Option<String>, default: WorkDate) -> Result<WorkDate> {
+pub fn build_date(date: Option<String>, default: WorkDate) -> Result<WorkDate> {
     let date = match date {
         Some(s) => WorkDate::parse_from_str(&s)?,
         None => default,
@@ -33,25 +77,63 @@ fn build_date(date: Option<String>, default: WorkDate) -> Result<WorkDate> {
 }
 
 // Print task log
-fn print_list(tasklist: TaskList) -> Result<()> {
+fn print_list(tasklist: TaskList, running_id: Option<u32>, verbose: bool) -> Result<()> {
     let mut table = Table::new();
     table.set_format(*format::consts::FORMAT_CLEAN);
 
+    // tags are shown only if any task entry has them
+    let show_tags = tasklist.has_tags();
+
     // title
-    table.set_titles(row![b => "Date", "No", "Start", "End", "Duration", "Task"]);
+    let mut titles = row![b => "Date", "No", "Start", "End", "Duration", "Task"];
+    if show_tags {
+        titles.add_cell(Cell::new("Tags").style_spec("b"));
+    }
+    if verbose {
+        titles.add_cell(Cell::new("Ref").style_spec("b"));
+        titles.add_cell(Cell::new("Note").style_spec("b"));
+    }
+    table.set_titles(titles);
 
     // contents
     for (n, task) in tasklist {
         let date = task.working_date().to_string();
         let start = task.start_time().to_string_hhmm();
+        let is_running = task.end_time().is_some() && *task.id() == running_id;
         let end = match task.end_time() {
+            Some(_) if is_running => String::from("now"),
             Some(t) => t.to_string_hhmm(),
             None => String::from(""),
         };
         let duration = task.duration_hhmm();
         let name = task.name();
 
-        table.add_row(row![date, r -> n, start, end, r -> duration, name]);
+        // highlight the running task
+        let style = if is_running { "Fg" } else { "" };
+        let mut cells = vec![
+            Cell::new(&date).style_spec(style),
+            Cell::new(&n.to_string()).style_spec(&format!("r{}", style)),
+            Cell::new(&start).style_spec(style),
+            Cell::new(&end).style_spec(style),
+            Cell::new(&duration).style_spec(&format!("r{}", style)),
+            Cell::new(name).style_spec(style),
+        ];
+        if show_tags {
+            let tags = task
+                .tags()
+                .iter()
+                .map(|tag| format!("#{}", tag))
+                .collect::<Vec<_>>()
+                .join(" ");
+            cells.push(Cell::new(&tags).style_spec(style));
+        }
+        if verbose {
+            let external_ref = task.external_ref().as_deref().unwrap_or("");
+            let note = task.note().as_deref().unwrap_or("");
+            cells.push(Cell::new(external_ref).style_spec(style));
+            cells.push(Cell::new(note).style_spec(style));
+        }
+        table.add_row(Row::new(cells));
     }
     table.printstd();
 
@@ -59,7 +141,12 @@ fn print_list(tasklist: TaskList) -> Result<()> {
 }
 
 // Print task summary
-fn print_summary(task_summary: TaskSummary) -> Result<()> {
+fn print_summary(
+    task_summary: TaskSummary,
+    chart: bool,
+    group_by: &str,
+    projects: &ProjectMap,
+) -> Result<()> {
     // table formats
     let container_format = *format::consts::FORMAT_CLEAN;
     let table_format = format::FormatBuilder::new()
@@ -76,16 +163,54 @@ fn print_summary(task_summary: TaskSummary) -> Result<()> {
 
     // build tables
     let table_daily_overall = build_daily_total_table(&task_summary, table_format);
-    let table_task_durations = build_task_total_table(&task_summary, table_format);
+    let project_of = |name: &str| {
+        projects
+            .get(name)
+            .and_then(|(project, _)| project.clone())
+            .unwrap_or_else(|| String::from(UNASSIGNED))
+    };
+    let client_of = |name: &str| {
+        projects
+            .get(name)
+            .and_then(|(_, client)| client.clone())
+            .unwrap_or_else(|| String::from(UNASSIGNED))
+    };
+    let table_task_durations = match group_by {
+        "project" => build_rollup_table(
+            &task_summary,
+            row!["Project", "Task", "Duration"],
+            table_format,
+            |name| (project_of(name), name.to_string()),
+        ),
+        "client" => build_rollup_table(
+            &task_summary,
+            row!["Client", "Project", "Duration"],
+            table_format,
+            |name| (client_of(name), project_of(name)),
+        ),
+        _ if chart => build_task_chart_table(&task_summary, table_format),
+        _ => build_task_total_table(&task_summary, table_format),
+    };
     let table_break_times = build_break_time_table(&task_summary, table_format);
 
     // print
     let mut summary_table = table!(
         [b => "Summary"],
         [table_daily_overall], [""],
-        [table_task_durations], [""],
-        [table_break_times]
+        [table_task_durations], [""]
     );
+    if !task_summary.duration_by_tag().is_empty() {
+        let table_tag_durations = build_tag_total_table(&task_summary, table_format);
+        summary_table.add_row(row![table_tag_durations]);
+        summary_table.add_row(row![""]);
+    }
+    summary_table.add_row(row![table_break_times]);
+    if !task_summary.duration_by_break_category().is_empty() {
+        let table_category_durations =
+            build_break_category_total_table(&task_summary, table_format);
+        summary_table.add_row(row![""]);
+        summary_table.add_row(row![table_category_durations]);
+    }
     summary_table.set_format(container_format);
     summary_table.printstd();
 
@@ -129,12 +254,88 @@ fn build_task_total_table(task_summary: &TaskSummary, format: format::TableForma
     tab
 }
 
+/// Create tag duration table.
+fn build_tag_total_table(task_summary: &TaskSummary, format: format::TableFormat) -> Table {
+    let duration_map = task_summary.duration_by_tag(); // key: tag, value: duration
+
+    let mut tags = duration_map.keys().cloned().collect::<Vec<String>>();
+    tags.sort();
+
+    let mut tab = build_summary_table_structure(row!["Tag", "Duration"], format);
+    for tag in tags {
+        let dur = duration_map.get(&tag).unwrap().to_string_hhmm();
+        tab.add_row(row![l -> format!("#{}", tag), r -> dur]);
+    }
+
+    tab
+}
+
+/// Create break category duration table.
+fn build_break_category_total_table(
+    task_summary: &TaskSummary,
+    format: format::TableFormat,
+) -> Table {
+    let duration_map = task_summary.duration_by_break_category(); // key: category, value: duration
+
+    let mut categories = duration_map.keys().cloned().collect::<Vec<String>>();
+    categories.sort();
+
+    let mut tab = build_summary_table_structure(row!["Category", "Duration"], format);
+    for category in categories {
+        let dur = duration_map.get(&category).unwrap().to_string_hhmm();
+        tab.add_row(row![l -> category, r -> dur]);
+    }
+
+    tab
+}
+
+/// Create task duration table with bars, sorted by duration.
+fn build_task_chart_table(task_summary: &TaskSummary, format: format::TableFormat) -> Table {
+    let mut tab = build_summary_table_structure(row!["Task", "Duration", "Share", ""], format);
+    for (task_name, duration, share) in task_summary.task_shares() {
+        let dur = duration.to_string_hhmm();
+        let percent = format!("{:.1}%", share * 100.0);
+        let bar = "█".repeat((share * CHART_WIDTH as f64).round() as usize);
+        tab.add_row(row![l -> task_name, r -> dur, r -> percent, l -> bar]);
+    }
+
+    tab
+}
+
+/// Create duration table grouped by `key`, which maps a task name to a group and an item,
+/// with subtotals of the groups.
+fn build_rollup_table<F>(
+    task_summary: &TaskSummary,
+    title: Row,
+    format: format::TableFormat,
+    key: F,
+) -> Table
+where
+    F: Fn(&str) -> (String, String),
+{
+    let mut tab = build_summary_table_structure(title, format);
+    for (group, items) in task_summary.rollup(key) {
+        let subtotal = items
+            .values()
+            .fold(Duration::seconds(0), |acc, duration| acc + *duration);
+
+        let mut group_label = group;
+        for (item, duration) in items {
+            tab.add_row(row![l -> group_label, l -> item, r -> duration.to_string_hhmm()]);
+            group_label = String::from("");
+        }
+        tab.add_row(row![l -> "", i -> "Subtotal", ri -> subtotal.to_string_hhmm()]);
+    }
+
+    tab
+}
+
 /// Create break time list table.
 fn build_break_time_table(task_summary: &TaskSummary, format: format::TableFormat) -> Table {
-    let mut tab = build_summary_table_structure(row!["Break"], format);
+    let mut tab = build_summary_table_structure(row!["Break", "Category"], format);
 
     if task_summary.break_times().is_empty() {
-        tab.add_row(row!["NA"]);
+        tab.add_row(row!["NA", ""]);
     } else {
         for break_time in task_summary.break_times() {
             let start = break_time.start_time().to_string_hhmm();
@@ -142,7 +343,11 @@ fn build_break_time_table(task_summary: &TaskSummary, format: format::TableForma
                 Some(t) => t.to_string_hhmm(),
                 None => String::from(""),
             };
-            tab.add_row(row![start + " - " + &end]);
+            let category = break_time
+                .break_category()
+                .as_ref()
+                .map_or("", |category| category.name());
+            tab.add_row(row![start + " - " + &end, category]);
         }
     }
 
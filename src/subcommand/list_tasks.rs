@@ -2,10 +2,15 @@ use crate::db::Database;
 use anyhow::Result;
 use prettytable::{format, Table};
 
-/// Remove a task name from the database.
-pub fn run(db: &Database) -> Result<()> {
-    let tasknames = db.get_registered_tasknames()?;
-    print_tasknames(tasknames);
+/// Print registered task names, or archived ones.
+pub fn run(db: &Database, archived: bool) -> Result<()> {
+    if archived {
+        let tasknames = db.get_archived_tasknames()?;
+        print_archived_tasknames(tasknames);
+    } else {
+        let tasknames = db.get_registered_tasknames()?;
+        print_tasknames(tasknames);
+    }
 
     Ok(())
 }
@@ -25,3 +30,19 @@ fn print_tasknames(tasknames: Vec<(u32, String)>) {
 
     table.printstd();
 }
+
+/// Print archived task names as a table format.
+fn print_archived_tasknames(tasknames: Vec<String>) {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+
+    // title
+    table.add_row(row![bl -> "Archived task"]);
+
+    // contents
+    for task_name in tasknames {
+        table.add_row(row![l -> task_name]);
+    }
+
+    table.printstd();
+}
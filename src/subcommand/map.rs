@@ -0,0 +1,70 @@
+use crate::db::Database;
+use anyhow::Result;
+use prettytable::{format, Table};
+use std::env;
+use std::path::{Component, PathBuf};
+
+/// Map directories matching a glob pattern to a registered task name.
+pub fn add(db: &mut Database, pattern: &str, task_name: &str) -> Result<()> {
+    let pattern = normalize_pattern(pattern)?;
+    if let Err(e) = db.add_dir_mapping(&pattern, task_name) {
+        eprintln!("{}: {}", e, task_name);
+    }
+
+    Ok(())
+}
+
+/// Remove a mapping of directories.
+pub fn remove(db: &mut Database, pattern: &str) -> Result<()> {
+    let pattern = normalize_pattern(pattern)?;
+    if let Err(e) = db.remove_dir_mapping(&pattern) {
+        eprintln!("{}: {}", e, pattern);
+    }
+
+    Ok(())
+}
+
+/// Print mappings of directories as a table format.
+pub fn list(db: &Database) -> Result<()> {
+    let mappings = db.get_dir_mappings()?;
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+
+    // title
+    table.add_row(row![bl -> "Directory", bl -> "Task"]);
+
+    // contents
+    for (pattern, task_name) in mappings {
+        table.add_row(row![l -> pattern, l -> task_name]);
+    }
+
+    table.printstd();
+
+    Ok(())
+}
+
+/// Make a pattern absolute, expanding a leading `~` to the home directory and resolving
+/// a relative pattern from the current directory.
+fn normalize_pattern(pattern: &str) -> Result<String> {
+    let path = match pattern.strip_prefix('~') {
+        Some(rest) => {
+            let home = env::var("HOME").map(PathBuf::from)?;
+            home.join(rest.trim_start_matches('/'))
+        }
+        None => env::current_dir()?.join(pattern),
+    };
+
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            c => normalized.push(c),
+        }
+    }
+
+    Ok(normalized.to_string_lossy().to_string())
+}
@@ -0,0 +1,96 @@
+use crate::db::Database;
+use crate::task::{Task, WorkDate};
+use anyhow::{anyhow, Result};
+
+/// Merge the task entries from `first` to `last` into one.
+///
+/// Entries separated by a gap, or breaks and other entries, are merged only if `force` is set.
+pub fn run(db: &mut Database, first: u32, last: u32, force: bool) -> Result<()> {
+    let (first, last) = (first.min(last), first.max(last));
+    if first == last {
+        return Err(anyhow!("entries to merge are the same"));
+    }
+
+    let working_date = WorkDate::now();
+    db.get_task_id_by_seqnum(first, working_date)?;
+    db.get_task_id_by_seqnum(last, working_date)?;
+
+    let tasks = db
+        .get_tasks(false, Some(working_date))?
+        .into_iter()
+        .filter(|(n, _)| first <= *n && *n <= last)
+        .map(|(_, task)| task)
+        .collect::<Vec<_>>();
+
+    if !force {
+        if tasks
+            .iter()
+            .any(|task| task.break_category() != tasks[0].break_category())
+        {
+            return Err(anyhow!(
+                "entries across breaks can not be merged without --force"
+            ));
+        }
+        if tasks
+            .windows(2)
+            .any(|pair| !pair[0].is_adjacent_to(&pair[1]))
+        {
+            return Err(anyhow!(
+                "entries with gaps can not be merged without --force"
+            ));
+        }
+    }
+
+    merge_tasks(db, &tasks)
+}
+
+/// Merge each run of adjacent task entries with the same name in the working date.
+pub fn auto(db: &mut Database) -> Result<()> {
+    let working_date = WorkDate::now();
+    let tasks = db
+        .get_tasks(false, Some(working_date))?
+        .into_iter()
+        .map(|(_, task)| task)
+        .collect::<Vec<_>>();
+
+    let mut runs: Vec<Vec<Task>> = Vec::new();
+    for task in tasks {
+        let continues = match runs.last().and_then(|run| run.last()) {
+            Some(prev) => prev.name() == task.name() && prev.is_adjacent_to(&task),
+            None => false,
+        };
+        if continues {
+            runs.last_mut().unwrap().push(task);
+        } else {
+            runs.push(vec![task]);
+        }
+    }
+
+    let runs = runs
+        .into_iter()
+        .filter(|run| run.len() > 1)
+        .collect::<Vec<_>>();
+    if runs.is_empty() {
+        println!("no entries to merge");
+    }
+    for run in runs {
+        merge_tasks(db, &run)?;
+    }
+
+    Ok(())
+}
+
+/// Merge task entries ordered by their start time into the first one.
+fn merge_tasks(db: &mut Database, tasks: &[Task]) -> Result<()> {
+    let (first, rest) = tasks.split_first().unwrap();
+    let merged = rest.iter().fold(first.clone(), |acc, task| acc.merge(task));
+    let merged_ids = rest
+        .iter()
+        .map(|task| task.id().unwrap())
+        .collect::<Vec<_>>();
+    db.merge_tasks(first.id().unwrap(), &merged, &merged_ids)?;
+
+    println!("{} merged from {} entries", merged.name(), tasks.len());
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use crate::db::Database;
+use crate::subcommand::end::end_task;
+use crate::task::{TaskTime, TimeDisplay};
+use anyhow::{anyhow, Result};
+use regex::Regex;
+
+/// End the current task and remember it to be resumed later.
+pub fn pause(db: &mut Database, time: Option<String>, ref_pattern: &Regex) -> Result<()> {
+    let end_time = match time {
+        Some(t) => TaskTime::parse_from_str_hhmm(&t)?,
+        None => TaskTime::now(),
+    };
+
+    let task_id = db
+        .get_current_task_id()?
+        .ok_or(anyhow!("no task is running"))?;
+    if db.get_task(task_id)?.is_break_time() {
+        return Err(anyhow!("break time can not be paused"));
+    }
+
+    db.pause_task(task_id)?;
+    end_task(db, task_id, &end_time, None, ref_pattern)?;
+
+    Ok(())
+}
+
+/// Start a new entry of the paused task with its tags and note.
+pub fn resume(db: &mut Database, time: Option<String>, ref_pattern: &Regex) -> Result<()> {
+    let start_time = match time {
+        Some(t) => TaskTime::parse_from_str_hhmm(&t)?,
+        None => TaskTime::now(),
+    };
+
+    let paused_id = db
+        .get_paused_task_id()?
+        .ok_or(anyhow!("no task is paused"))?;
+    let paused_task = db.get_task(paused_id)?;
+    if let Some(end_time) = paused_task.end_time() {
+        if start_time < *end_time {
+            return Err(anyhow!("resume time is before the pause"));
+        }
+    }
+
+    // end the task started during the pause
+    if let Some(current_task_id) = db.get_current_task_id()? {
+        end_task(db, current_task_id, &start_time, None, ref_pattern)?;
+    }
+
+    let new_task = paused_task.restart(start_time);
+    db.add_task_entry(&new_task)?;
+    db.set_paused_task_id(None)?;
+
+    println!(
+        "{} resumed at {}",
+        new_task.name(),
+        new_task.start_time().to_string_hhmm()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use crate::db::Database;
+use anyhow::Result;
+use prettytable::{format, Table};
+
+/// Add a project, or change the client of an existing project.
+pub fn add(db: &mut Database, project: &str, client: Option<&str>) -> Result<()> {
+    db.add_project(project, client)
+}
+
+/// Assign a task name to a project.
+pub fn assign(db: &mut Database, task_name: &str, project: Option<&str>) -> Result<()> {
+    if let Err(e) = db.assign_project(task_name, project) {
+        eprintln!("{}: {}", e, project.unwrap_or(task_name));
+    }
+
+    Ok(())
+}
+
+/// Print projects and their clients as a table format.
+pub fn list(db: &Database) -> Result<()> {
+    let projects = db.get_projects()?;
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+
+    // title
+    table.add_row(row![bl -> "Project", bl -> "Client"]);
+
+    // contents
+    for (project, client) in projects {
+        table.add_row(row![l -> project, l -> client.unwrap_or_default()]);
+    }
+
+    table.printstd();
+
+    Ok(())
+}
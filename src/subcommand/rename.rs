@@ -0,0 +1,13 @@
+use crate::db::Database;
+use anyhow::Result;
+
+/// Rename a task name, merging it if the new name already exists.
+pub fn run(db: &mut Database, old: &str, new: &str, keep_history: bool) -> Result<()> {
+    match db.rename_taskname(old, new, keep_history) {
+        Ok(true) => println!("\"{}\" merged into \"{}\"", old, new),
+        Ok(false) => println!("\"{}\" renamed to \"{}\"", old, new),
+        Err(e) => eprintln!("{}: {}", e, old),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use crate::db::Database;
+use crate::subcommand::list_log::build_date;
+use crate::task::{TimeDisplay, WorkDate};
+use anyhow::{anyhow, Result};
+use chrono::Duration;
+use prettytable::{format, Table};
+
+/// Print total working durations per external reference, git repository or branch for a
+/// period, from the start of this week to today by default.
+pub fn run(db: &Database, target: &str, from: Option<String>, to: Option<String>) -> Result<()> {
+    let today = WorkDate::now();
+    let first = build_date(from, today.week_start())?;
+    let last = build_date(to, today)?;
+    if first > last {
+        return Err(anyhow!("the first date is after the last date"));
+    }
+
+    let tasks = db.get_tasks_between(first, last)?;
+    let (title, durations) = match target {
+        "ref" => (
+            "Ref",
+            tasks.working_duration_by(|task| task.external_ref().clone()),
+        ),
+        "repo" => (
+            "Repository",
+            tasks.working_duration_by(|task| task.repo().clone()),
+        ),
+        "branch" => (
+            "Repository (branch)",
+            tasks.working_duration_by(|task| {
+                task.repo().as_ref().map(|repo| match task.branch() {
+                    Some(branch) => format!("{} ({})", repo, branch),
+                    None => repo.clone(),
+                })
+            }),
+        ),
+        _ => return Err(anyhow!("unknown report: {}", target)),
+    };
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+
+    // title
+    table.set_titles(row![bl -> title, br -> "Duration"]);
+
+    // contents
+    let mut total = Duration::seconds(0);
+    for (key, duration) in durations {
+        total = total + duration;
+        table.add_row(row![l -> key, r -> duration.to_string_hhmm()]);
+    }
+    table.add_row(row![i -> "Total", ri -> total.to_string_hhmm()]);
+
+    println!("{} - {}", first, last);
+    table.printstd();
+
+    Ok(())
+}
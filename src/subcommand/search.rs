@@ -0,0 +1,62 @@
+use crate::db::{Database, HIGHLIGHT_END, HIGHLIGHT_START};
+use crate::task::TimeDisplay;
+use anyhow::{anyhow, Result};
+use std::io::Write;
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+
+/// Print task entries whose names or notes match the query.
+pub fn run(db: &Database, query: &str, limit: u32) -> Result<()> {
+    if query.trim().is_empty() {
+        return Err(anyhow!("query is empty"));
+    }
+
+    let hits = db.search_tasks(query, limit)?;
+    if hits.is_empty() {
+        eprintln!("No task entries matched: {}", query);
+        return Ok(());
+    }
+
+    let mut stdout = StandardStream::stdout(ColorChoice::Auto);
+    for hit in hits {
+        let task = hit.task();
+        let end = match task.end_time() {
+            Some(t) => t.to_string_hhmm(),
+            None => String::from(""),
+        };
+        write!(
+            &mut stdout,
+            "{}  {} - {:<5}  ",
+            task.working_date(),
+            task.start_time().to_string_hhmm(),
+            end
+        )?;
+        write_highlighted(&mut stdout, hit.name())?;
+        if let Some(note) = hit.note() {
+            write!(&mut stdout, "  ")?;
+            write_highlighted(&mut stdout, note)?;
+        }
+        writeln!(&mut stdout)?;
+    }
+
+    Ok(())
+}
+
+/// Write a text in which parts enclosed in the highlight markers are emphasized.
+fn write_highlighted(out: &mut StandardStream, text: &str) -> Result<()> {
+    let mut spec = ColorSpec::new();
+    spec.set_fg(Some(Color::Yellow)).set_bold(true);
+
+    for (i, part) in text.split(HIGHLIGHT_START).enumerate() {
+        match part.split_once(HIGHLIGHT_END) {
+            Some((matched, rest)) if i > 0 => {
+                out.set_color(&spec)?;
+                write!(out, "{}", matched)?;
+                out.reset()?;
+                write!(out, "{}", rest)?;
+            }
+            _ => write!(out, "{}", part)?,
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::db::Database;
+use crate::subcommand::start::resolve_taskname;
+use crate::task::{TaskTime, TimeDisplay, WorkDate};
+use anyhow::Result;
+use regex::Regex;
+
+/// Split a task entry at the time into two adjacent entries, making the second one a working
+/// task of `new_task_name` if given, which is resolved like `start`.
+pub fn run(
+    db: &mut Database,
+    task_number: u32,
+    time: String,
+    new_task_name: Option<String>,
+    ref_pattern: &Regex,
+) -> Result<()> {
+    let working_date = WorkDate::now();
+    let split_time = TaskTime::parse_from_str_hhmm(&time)?;
+
+    let task_id = db.get_task_id_by_seqnum(task_number, working_date)?;
+    let (first, mut second) = db.get_task(task_id)?.split_at(split_time)?;
+    if let Some(pattern) = new_task_name {
+        // the category and the reference of the first part belong to the old name
+        second = second.into_task_named(resolve_taskname(db, Some(pattern))?, ref_pattern);
+    }
+    db.split_task(task_id, &first, &second)?;
+
+    if first.name() == second.name() {
+        println!("{} split at {}", first.name(), split_time.to_string_hhmm());
+    } else {
+        println!(
+            "{} split at {}, the rest is {}",
+            first.name(),
+            split_time.to_string_hhmm(),
+            second.name()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use crate::db::Database;
+use crate::subcommand::end::end_task;
+use crate::subcommand::start::{self, StartOptions};
+use crate::task::{TaskTime, TimeDisplay};
+use anyhow::{anyhow, Result};
+use prettytable::{format, Table};
+use regex::Regex;
+
+/// Suspend the current task onto the stack and start an interruption.
+pub fn push(db: &mut Database, opts: StartOptions, ref_pattern: &Regex) -> Result<()> {
+    let task_id = db
+        .get_current_task_id()?
+        .ok_or(anyhow!("no task is running"))?;
+    if db.get_task(task_id)?.is_break_time() {
+        return Err(anyhow!("break time can not be suspended"));
+    }
+
+    start::run(db, opts, ref_pattern)?;
+    db.push_task_stack(task_id)?;
+
+    Ok(())
+}
+
+/// End the interruption and resume the task on the top of the stack.
+pub fn pop(db: &mut Database, time: Option<String>, ref_pattern: &Regex) -> Result<()> {
+    let start_time = match time {
+        Some(t) => TaskTime::parse_from_str_hhmm(&t)?,
+        None => TaskTime::now(),
+    };
+
+    let task_id = db
+        .get_task_stack()?
+        .first()
+        .copied()
+        .ok_or(anyhow!("task stack is empty"))?;
+    let suspended_task = db.get_task(task_id)?;
+
+    // end the interruption
+    if let Some(current_task_id) = db.get_current_task_id()? {
+        end_task(db, current_task_id, &start_time, None, ref_pattern)?;
+    }
+
+    let new_task = suspended_task.restart(start_time);
+    db.add_task_entry(&new_task)?;
+    db.pop_task_stack()?;
+
+    println!(
+        "{} resumed at {}",
+        new_task.name(),
+        new_task.start_time().to_string_hhmm()
+    );
+
+    Ok(())
+}
+
+/// Print the running task and the suspended tasks from the top of the stack.
+pub fn show(db: &Database) -> Result<()> {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+
+    // title
+    table.add_row(row![bl -> "Task", bl -> "State", bl -> "Since"]);
+
+    // contents
+    if let Some(task_id) = db.get_current_task_id()? {
+        let task = db.get_task(task_id)?;
+        table.add_row(row![
+            l -> task.name(),
+            l -> "running",
+            l -> task.start_time().to_string_hhmm()
+        ]);
+    }
+    for task_id in db.get_task_stack()? {
+        let task = db.get_task(task_id)?;
+        let since = match task.end_time() {
+            Some(t) => t.to_string_hhmm(),
+            None => String::from(""),
+        };
+        table.add_row(row![l -> task.name(), l -> "suspended", l -> since]);
+    }
+
+    table.printstd();
+
+    Ok(())
+}
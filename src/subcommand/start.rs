@@ -1,31 +1,89 @@
 use crate::db::Database;
+use crate::git;
+use crate::matcher::{find_mapped_taskname, find_tasknames};
+use crate::parser::parse_tag;
 use crate::subcommand::end::end_task;
 use crate::task::{Task, TaskTime, TimeDisplay};
 use anyhow::{anyhow, Result};
+use console::user_attended_stderr;
+use dialoguer::Select;
+use regex::Regex;
+use std::env;
 
-pub fn run(
-    db: &mut Database,
-    taskname_number: Option<u32>,
-    is_break_time: bool,
-    time: Option<String>,
-    break_taskname: &str,
-) -> Result<()> {
-    let start_time = build_start_time(time, TaskTime::now())?;
+/// Options of a task entry to be started.
+pub struct StartOptions {
+    /// Task number, name, or its prefix or fuzzy pattern, picked interactively if not given.
+    pub task: Option<String>,
+    /// Break category to start instead of a task.
+    pub break_category: Option<String>,
+    /// Start time in `HHMM` format, now if not given.
+    pub time: Option<String>,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+    /// External reference, extracted from the name or the note if not given.
+    pub external_ref: Option<String>,
+}
+
+pub fn run(db: &mut Database, opts: StartOptions, ref_pattern: &Regex) -> Result<()> {
+    let start_time = build_start_time(opts.time, TaskTime::now())?;
+    let tags = opts
+        .tags
+        .iter()
+        .map(|tag| parse_tag(tag))
+        .collect::<Result<Vec<_>>>()?;
+
+    // choose the task name before ending the current task, a break is named after its category
+    let break_category = match opts.break_category {
+        Some(name) => Some(
+            db.get_break_category(&name)?
+                .ok_or_else(|| anyhow!("category does not exist: {}", name))?,
+        ),
+        None => None,
+    };
+    let new_task_name = match &break_category {
+        Some(category) => category.name().clone(),
+        None => resolve_taskname(db, opts.task)?,
+    };
 
+    let new_task = Task::start(new_task_name, start_time, break_category)
+        .with_tags(tags)
+        .with_note(opts.note)
+        .with_external_ref(opts.external_ref);
+
+    start_task(db, new_task, ref_pattern)
+}
+
+/// End the current task and add a new task entry, which is started at its start time.
+pub fn start_task(db: &mut Database, new_task: Task, ref_pattern: &Regex) -> Result<()> {
     // end current task
     if let Some(current_task_id) = db.get_current_task_id()? {
-        end_task(db, current_task_id, &start_time)?;
+        end_task(
+            db,
+            current_task_id,
+            new_task.start_time(),
+            None,
+            ref_pattern,
+        )?;
     }
 
-    // start new task
-    let new_task_name = match is_break_time {
-        true => String::from(break_taskname),
-        false => match taskname_number {
-            Some(n) => db.get_taskname(n),
-            None => Err(anyhow!("Task number was not provided")),
-        }?,
+    // record the git repository of the current directory on working tasks
+    let repo = match new_task.is_break_time() {
+        true => None,
+        false => env::current_dir().ok().and_then(|dir| git::discover(&dir)),
     };
-    let new_task = register_task(db, new_task_name, start_time, is_break_time)?;
+    let (repo_root, branch) = match repo {
+        Some(repo) => (
+            Some(repo.root().to_string_lossy().to_string()),
+            repo.branch().clone(),
+        ),
+        None => (None, None),
+    };
+
+    // start new task
+    let new_task = new_task
+        .fill_external_ref(ref_pattern)
+        .with_repo(repo_root, branch);
+    db.add_task_entry(&new_task)?;
 
     println!(
         "{} started at {}",
@@ -36,6 +94,67 @@ pub fn run(
     Ok(())
 }
 
+/// Find a registered task name by a task number, name, prefix or fuzzy pattern.
+///
+/// If the pattern is not given, the task name mapped to the current directory is used.
+/// The user picks one interactively if the pattern is ambiguous, or not given and the current
+/// directory is not mapped.
+pub fn resolve_taskname(db: &Database, pattern: Option<String>) -> Result<String> {
+    let tasknames = db
+        .get_registered_tasknames()?
+        .into_iter()
+        .map(|(_, name)| name)
+        .collect::<Vec<_>>();
+
+    let pattern = match pattern {
+        Some(p) => p,
+        None => {
+            let mappings = db.get_dir_mappings()?;
+            let dir = env::current_dir()?;
+            return match find_mapped_taskname(&dir, &mappings) {
+                Some(name) => Ok(name.to_string()),
+                None => pick_taskname(&tasknames),
+            };
+        }
+    };
+
+    // an exact name takes precedence over a task number, e.g. a task named `2021`
+    if tasknames.contains(&pattern) {
+        return Ok(pattern);
+    }
+    if let Ok(n) = pattern.parse::<u32>() {
+        return db.get_taskname(n);
+    }
+
+    let candidates = find_tasknames(&pattern, &tasknames);
+    match candidates.len() {
+        0 => Err(anyhow!("task not found: {}", pattern)),
+        1 => Ok(candidates[0].clone()),
+        _ => pick_taskname(&candidates),
+    }
+}
+
+/// Let the user choose a task name from the candidates.
+fn pick_taskname(candidates: &[String]) -> Result<String> {
+    if candidates.is_empty() {
+        return Err(anyhow!("no task names are registered"));
+    }
+    if !user_attended_stderr() {
+        return Err(anyhow!("task is ambiguous: {}", candidates.join(", ")));
+    }
+
+    let selection = Select::new()
+        .with_prompt("Select a task")
+        .items(candidates)
+        .default(0)
+        .interact_opt()?;
+
+    match selection {
+        Some(i) => Ok(candidates[i].clone()),
+        None => Err(anyhow!("no task selected")),
+    }
+}
+
 /// Build `TaskTime` form `HHMM` string.
 fn build_start_time(time: Option<String>, default: TaskTime) -> Result<TaskTime> {
     let start_time = match time {
@@ -45,16 +164,3 @@ fn build_start_time(time: Option<String>, default: TaskTime) -> Result<TaskTime>
 
     Ok(start_time)
 }
-
-// start a task and register it to the database
-fn register_task(
-    db: &mut Database,
-    task_name: String,
-    start_time: TaskTime,
-    is_break_time: bool,
-) -> Result<Task> {
-    let new_task = Task::start(task_name, start_time, is_break_time);
-    db.add_task_entry(&new_task)?;
-
-    Ok(new_task)
-}
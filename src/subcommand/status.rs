@@ -0,0 +1,46 @@
+use crate::db::Database;
+use crate::parser::fill_placeholders;
+use crate::task::{TaskTime, TimeDisplay, WorkDate};
+use anyhow::Result;
+use chrono::Duration;
+
+/// Output format used when `--format` is not given.
+const DEFAULT_FORMAT: &str = "{name} started at {start}, {elapsed} elapsed ({total} today)";
+
+/// Print the current task status, returns `false` if no task is running.
+pub fn run(db: &Database, format: Option<String>) -> Result<bool> {
+    let manager = db.get_manager()?;
+    let (task_id, task_name, start_time) =
+        match (manager.task_id(), manager.task_name(), manager.start_time()) {
+            (Some(id), Some(name), Some(time)) => (*id, name.clone(), *time),
+            _ => {
+                match manager.paused_task_id() {
+                    Some(id) => eprintln!(
+                        "No task is running ({} is paused)",
+                        db.get_task(*id)?.name()
+                    ),
+                    None => eprintln!("No task is running"),
+                }
+                return Ok(false);
+            }
+        };
+
+    let now = TaskTime::now();
+    let elapsed = now - start_time;
+    let total = db
+        .get_tasks(false, Some(WorkDate::now()))?
+        .end_running_at(task_id, now)
+        .summary()
+        .map_or(Duration::seconds(0), |summary| *summary.duration_total());
+
+    let format = format.unwrap_or_else(|| String::from(DEFAULT_FORMAT));
+    let values = [
+        ("name", task_name),
+        ("start", start_time.to_string_hhmm()),
+        ("elapsed", elapsed.to_string_hhmm()),
+        ("total", total.to_string_hhmm()),
+    ];
+    println!("{}", fill_placeholders(&format, &values));
+
+    Ok(true)
+}
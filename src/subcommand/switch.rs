@@ -0,0 +1,70 @@
+use crate::db::Database;
+use crate::subcommand::start::start_task;
+use crate::task::{Task, TaskTime};
+use anyhow::{anyhow, Result};
+use prettytable::{format, Table};
+use regex::Regex;
+
+/// Start the task of the number in the recent task names, or the most recent one if not given.
+pub fn switch(
+    db: &mut Database,
+    number: Option<u32>,
+    time: Option<String>,
+    ref_pattern: &Regex,
+) -> Result<()> {
+    let start_time = match time {
+        Some(t) => TaskTime::parse_from_str_hhmm(&t)?,
+        None => TaskTime::now(),
+    };
+
+    let number = number.unwrap_or(1);
+    let tasknames = recent_tasknames(db, number)?;
+    let task_name = match number {
+        0 => None,
+        n => tasknames.get(n as usize - 1),
+    }
+    .ok_or(anyhow!("recent task does not exist: {}", number))?;
+
+    start_task(
+        db,
+        Task::start(task_name.clone(), start_time, None),
+        ref_pattern,
+    )
+}
+
+/// Print recent task names with their numbers used by `switch`.
+pub fn recent(db: &Database, limit: u32) -> Result<()> {
+    let tasknames = recent_tasknames(db, limit)?;
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+
+    // title
+    table.add_row(row![br -> "No", bl -> "Task"]);
+
+    // contents
+    for (i, task_name) in tasknames.iter().enumerate() {
+        table.add_row(row![r -> i + 1, l -> task_name]);
+    }
+
+    table.printstd();
+
+    Ok(())
+}
+
+/// Get distinct task names from the most recently started one, except the running task.
+fn recent_tasknames(db: &Database, limit: u32) -> Result<Vec<String>> {
+    let running_name = match db.get_current_task_id()? {
+        Some(id) => Some(db.get_task(id)?.name().clone()),
+        None => None,
+    };
+
+    let tasknames = db
+        .get_recent_tasknames(limit.saturating_add(1))?
+        .into_iter()
+        .filter(|name| Some(name) != running_name.as_ref())
+        .take(limit as usize)
+        .collect();
+
+    Ok(tasknames)
+}
@@ -0,0 +1,183 @@
+use crate::db::Database;
+use crate::subcommand::list_log::build_date;
+use crate::task::{Task, TaskTime, WorkDate};
+use anyhow::Result;
+use chrono::Duration;
+use console::Term;
+use std::io::Write;
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+
+/// Colors assigned to task names in alphabetical order.
+const PALETTE: [Color; 6] = [
+    Color::Blue,
+    Color::Green,
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Red,
+];
+
+/// Minimum number of columns used for bars.
+const MIN_BAR_WIDTH: usize = 24;
+
+/// Print a timeline of task entries on a day.
+pub fn run(db: &Database, date: Option<String>) -> Result<()> {
+    let date = build_date(date, WorkDate::now())?;
+    let tasks = db.get_tasks(false, Some(date))?;
+
+    // the running entry is drawn up to now only on the current working date
+    let now = TaskTime::now();
+    let entries = tasks
+        .into_iter()
+        .map(|(n, task)| {
+            let end = match task.end_time() {
+                Some(t) => *t,
+                None if task.working_date() == WorkDate::now() && now > *task.start_time() => now,
+                None => *task.start_time(),
+            };
+            (n, task, end)
+        })
+        .collect::<Vec<_>>();
+
+    if entries.is_empty() {
+        eprintln!("No task entries on {}", date);
+        return Ok(());
+    }
+
+    // whole hours covering all entries
+    let first = entries
+        .iter()
+        .map(|(_, task, _)| *task.start_time())
+        .min()
+        .unwrap()
+        .floor_hour();
+    let mut last = entries
+        .iter()
+        .map(|(_, _, end)| *end)
+        .max()
+        .unwrap()
+        .ceil_hour();
+    if last == first {
+        last = first + Duration::hours(1);
+    }
+
+    let labels = entries
+        .iter()
+        .map(|(n, task, _)| format!("{:>3}  {}", n, task.name()))
+        .collect::<Vec<_>>();
+    let label_width = labels.iter().map(|l| l.chars().count()).max().unwrap() + 2;
+
+    let (_, columns) = Term::stdout().size();
+    let bar_width = (columns as usize)
+        .saturating_sub(label_width + 1)
+        .max(MIN_BAR_WIDTH);
+    let scale = Scale::new(first, last, bar_width);
+
+    let colors = assign_colors(entries.iter().map(|(_, task, _)| task));
+
+    let mut stdout = StandardStream::stdout(ColorChoice::Auto);
+    write_ruler(&mut stdout, &scale, label_width)?;
+    for ((_, task, end), label) in entries.iter().zip(labels) {
+        write!(&mut stdout, "{:<width$}", label, width = label_width)?;
+        write_bar(&mut stdout, &scale, task, end, &colors)?;
+    }
+
+    Ok(())
+}
+
+/// Mapping from times to columns of the timeline.
+struct Scale {
+    start: TaskTime,
+    end: TaskTime,
+    width: usize,
+}
+
+impl Scale {
+    fn new(start: TaskTime, end: TaskTime, width: usize) -> Self {
+        Self { start, end, width }
+    }
+
+    /// Column of the time, `0` for the start and `width` for the end of the scale.
+    fn column(&self, time: &TaskTime) -> usize {
+        let total = (self.end - self.start).num_minutes();
+        let offset = (*time - self.start).num_minutes().clamp(0, total);
+        (offset * self.width as i64 / total) as usize
+    }
+}
+
+/// Pick a color for each working task name.
+fn assign_colors<'a>(tasks: impl Iterator<Item = &'a Task>) -> Vec<(String, Color)> {
+    let mut names = tasks
+        .filter(|task| !task.is_break_time())
+        .map(|task| task.name().to_string())
+        .collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| (name, PALETTE[i % PALETTE.len()]))
+        .collect()
+}
+
+/// Write the hour labels and the ruler line.
+fn write_ruler(out: &mut StandardStream, scale: &Scale, indent: usize) -> Result<()> {
+    let mut labels = vec![' '; scale.width + 3];
+    let mut ticks = vec!['-'; scale.width + 1];
+
+    let mut hour = scale.start;
+    let mut next_free = 0;
+    while hour <= scale.end {
+        let col = scale.column(&hour);
+        ticks[col] = '+';
+        // skip labels which would overlap the previous one
+        if col >= next_free {
+            for (i, c) in format!("{:02}", hour.hour()).chars().enumerate() {
+                labels[col + i] = c;
+            }
+            next_free = col + 3;
+        }
+        hour = hour + Duration::hours(1);
+    }
+
+    let labels = labels.into_iter().collect::<String>();
+    let ticks = ticks.into_iter().collect::<String>();
+    writeln!(out, "{:indent$}{}", "", labels.trim_end(), indent = indent)?;
+    writeln!(out, "{:indent$}{}", "", ticks, indent = indent)?;
+
+    Ok(())
+}
+
+/// Write a bar of the task, breaks are drawn in a dimmed shade.
+fn write_bar(
+    out: &mut StandardStream,
+    scale: &Scale,
+    task: &Task,
+    end: &TaskTime,
+    colors: &[(String, Color)],
+) -> Result<()> {
+    let start_col = scale.column(task.start_time());
+    let end_col = scale.column(end).max(start_col + 1);
+
+    let mut spec = ColorSpec::new();
+    let bar_char = if task.is_break_time() {
+        spec.set_dimmed(true);
+        "░"
+    } else {
+        let color = colors
+            .iter()
+            .find(|(name, _)| name == task.name())
+            .map(|(_, color)| *color);
+        spec.set_fg(color);
+        "█"
+    };
+
+    write!(out, "{}", " ".repeat(start_col))?;
+    out.set_color(&spec)?;
+    write!(out, "{}", bar_char.repeat(end_col - start_col))?;
+    out.reset()?;
+    writeln!(out)?;
+
+    Ok(())
+}
@@ -1,19 +1,46 @@
 use crate::db::Database;
+use crate::parser::parse_tag_changes;
 use crate::task::{TaskTime, WorkDate};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use regex::Regex;
 
-pub fn run(db: &Database, task_number: u32, target: String, value: String) -> Result<()> {
+pub fn run(
+    db: &Database,
+    task_number: u32,
+    target: String,
+    values: Vec<String>,
+    ref_pattern: &Regex,
+) -> Result<()> {
     let working_date = WorkDate::now();
 
     let task_id = db.get_task_id_by_seqnum(task_number, working_date)?;
     let mut task = db.get_task(task_id)?;
 
-    if target == "name" {
-        task.set_name(value);
-    } else if target == "start" {
-        task.set_start_time(TaskTime::parse_from_str_hhmm(&value)?);
-    } else if target == "end" {
-        task.set_end_time(Some(TaskTime::parse_from_str_hhmm(&value)?));
+    if target == "tags" {
+        let (add, remove) = parse_tag_changes(&values)?;
+        task = task.change_tags(&add, &remove);
+    } else if target == "note" {
+        let note = values.join(" ");
+        task = task.change_note(if note.is_empty() { None } else { Some(note) }, ref_pattern);
+    } else if target == "ref" {
+        let external_ref = values.join(" ");
+        task.set_external_ref(if external_ref.is_empty() {
+            None
+        } else {
+            Some(external_ref)
+        });
+    } else {
+        let value = values.join(" ");
+        if value.is_empty() {
+            return Err(anyhow!("value is not given"));
+        }
+        if target == "name" {
+            task = task.rename(value, ref_pattern);
+        } else if target == "start" {
+            task.set_start_time(TaskTime::parse_from_str_hhmm(&value)?);
+        } else if target == "end" {
+            task.set_end_time(Some(TaskTime::parse_from_str_hhmm(&value)?));
+        }
     }
 
     db.update_task(task.id().unwrap(), &task)
@@ -3,7 +3,8 @@ use anyhow::{anyhow, Result};
 use chrono::prelude::*;
 use chrono::Duration;
 use getset::{Getters, Setters};
-use std::collections::HashMap;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::ops;
 
@@ -23,6 +24,29 @@ impl TimeDisplay for Duration {
     }
 }
 
+/// Name of the break category used when a category is not given.
+pub const DEFAULT_BREAK_CATEGORY: &str = "break time";
+
+/// A category of non-working time, e.g. lunch or commute.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Getters)]
+pub struct BreakCategory {
+    #[getset(get = "pub")]
+    name: String,
+    /// Whether the time of the category counts towards working time.
+    #[getset(get = "pub")]
+    counts_as_work: bool,
+}
+
+impl BreakCategory {
+    /// Create a new break category.
+    pub fn new(name: String, counts_as_work: bool) -> Self {
+        Self {
+            name,
+            counts_as_work,
+        }
+    }
+}
+
 /// A task represents a task log.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Getters, Setters)]
 pub struct Task {
@@ -34,8 +58,21 @@ pub struct Task {
     start_time: TaskTime,
     #[getset(get = "pub", set = "pub")]
     end_time: Option<TaskTime>,
+    /// Category of a break, `None` for a working task.
     #[getset(get = "pub", set = "pub")]
-    is_break_time: bool,
+    break_category: Option<BreakCategory>,
+    #[getset(get = "pub")]
+    tags: Vec<String>,
+    #[getset(get = "pub", set = "pub")]
+    note: Option<String>,
+    #[getset(get = "pub", set = "pub")]
+    external_ref: Option<String>,
+    /// Root directory of the git repository in which the task was started.
+    #[getset(get = "pub")]
+    repo: Option<String>,
+    /// Git branch checked out when the task was started.
+    #[getset(get = "pub")]
+    branch: Option<String>,
 }
 
 impl Task {
@@ -45,20 +82,231 @@ impl Task {
         name: String,
         start_time: TaskTime,
         end_time: Option<TaskTime>,
-        is_break_time: bool,
+        break_category: Option<BreakCategory>,
     ) -> Self {
         Self {
             id,
             name,
             start_time,
             end_time,
-            is_break_time,
+            break_category,
+            tags: Vec::new(),
+            note: None,
+            external_ref: None,
+            repo: None,
+            branch: None,
+        }
+    }
+
+    /// Set a git repository and a branch of the task.
+    pub fn with_repo(self, repo: Option<String>, branch: Option<String>) -> Self {
+        Self {
+            repo,
+            branch,
+            ..self
         }
     }
 
+    /// Set a note of the task.
+    pub fn with_note(self, note: Option<String>) -> Self {
+        Self { note, ..self }
+    }
+
+    /// Set an external reference (e.g. an issue key) of the task.
+    pub fn with_external_ref(self, external_ref: Option<String>) -> Self {
+        Self {
+            external_ref,
+            ..self
+        }
+    }
+
+    /// Extract an external reference from the name or the note if the task has none.
+    ///
+    /// The first capture group of the pattern is used if it has one, otherwise the whole match.
+    pub fn fill_external_ref(self, pattern: &Regex) -> Self {
+        if self.external_ref.is_some() {
+            return self;
+        }
+
+        let external_ref = self.extract_external_ref(pattern);
+        self.with_external_ref(external_ref)
+    }
+
+    /// Rename the task, and extract the external reference again if it was extracted from
+    /// the name or the note.
+    pub fn rename(self, name: String, pattern: &Regex) -> Self {
+        let extracted = self.has_extracted_external_ref(pattern);
+        let task = Self { name, ..self };
+        task.refill_external_ref(extracted, pattern)
+    }
+
+    /// Change the note of the task, and extract the external reference again if it was
+    /// extracted from the name or the note.
+    pub fn change_note(self, note: Option<String>, pattern: &Regex) -> Self {
+        let extracted = self.has_extracted_external_ref(pattern);
+        let task = Self { note, ..self };
+        task.refill_external_ref(extracted, pattern)
+    }
+
+    /// Check whether the external reference is the one extracted from the name or the note,
+    /// rather than given explicitly.
+    fn has_extracted_external_ref(&self, pattern: &Regex) -> bool {
+        self.external_ref.is_none() || self.external_ref == self.extract_external_ref(pattern)
+    }
+
+    /// Replace the external reference with the one extracted from the name or the note if
+    /// `extracted` is set.
+    fn refill_external_ref(self, extracted: bool, pattern: &Regex) -> Self {
+        if extracted {
+            let external_ref = self.extract_external_ref(pattern);
+            self.with_external_ref(external_ref)
+        } else {
+            self
+        }
+    }
+
+    /// Find an external reference in the name, then in the note.
+    fn extract_external_ref(&self, pattern: &Regex) -> Option<String> {
+        std::iter::once(self.name.as_str())
+            .chain(self.note.as_deref())
+            .find_map(|text| pattern.captures(text))
+            .and_then(|captures| captures.get(1).or_else(|| captures.get(0)))
+            .map(|m| m.as_str().to_string())
+    }
+
+    /// Set tags of the task, which are sorted and deduplicated.
+    pub fn with_tags(self, tags: Vec<String>) -> Self {
+        let mut tags = tags;
+        tags.sort();
+        tags.dedup();
+        Self { tags, ..self }
+    }
+
+    /// Add and remove tags of the task.
+    pub fn change_tags(self, add: &[String], remove: &[String]) -> Self {
+        let tags = self
+            .tags
+            .iter()
+            .chain(add.iter())
+            .filter(|tag| !remove.contains(tag))
+            .cloned()
+            .collect();
+        self.with_tags(tags)
+    }
+
+    /// Check whether the task has the tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
     /// Start a new task.
-    pub fn start(name: String, time: TaskTime, is_break_time: bool) -> Self {
-        Self::new(None, name, time, None, is_break_time)
+    pub fn start(name: String, time: TaskTime, break_category: Option<BreakCategory>) -> Self {
+        Self::new(None, name, time, None, break_category)
+    }
+
+    /// Check whether the task is a break of any category.
+    pub fn is_break_time(&self) -> bool {
+        self.break_category.is_some()
+    }
+
+    /// Check whether the task counts towards working time.
+    pub fn is_working(&self) -> bool {
+        match &self.break_category {
+            Some(category) => category.counts_as_work,
+            None => true,
+        }
+    }
+
+    /// Start a new task with the same name, tags, note, external reference and repository
+    /// as the task.
+    pub fn restart(&self, time: TaskTime) -> Self {
+        Self {
+            id: None,
+            start_time: time,
+            end_time: None,
+            ..self.clone()
+        }
+    }
+
+    /// Check whether the ended task is reopened to continue at the time, which is the case if
+    /// the gap from its end is shorter than `max_gap`. Otherwise a new entry is started.
+    pub fn is_reopened_at(&self, time: TaskTime, max_gap: Duration) -> Result<bool> {
+        let end_time = self.end_time.ok_or(anyhow!("task is running"))?;
+        if time < end_time {
+            return Err(anyhow!("continue time is before the end of the task"));
+        }
+
+        Ok(time - end_time < max_gap)
+    }
+
+    /// Split the task at the time into two adjacent tasks, the first of which keeps the id.
+    pub fn split_at(&self, time: TaskTime) -> Result<(Self, Self)> {
+        let within = match self.end_time {
+            Some(end_time) => self.start_time < time && time < end_time,
+            None => self.start_time < time,
+        };
+        if !within {
+            return Err(anyhow!("split time is not within the task"));
+        }
+
+        let first = Self {
+            end_time: Some(time),
+            ..self.clone()
+        };
+        let second = Self {
+            id: None,
+            start_time: time,
+            ..self.clone()
+        };
+
+        Ok((first, second))
+    }
+
+    /// Make the task a working task of another name, whose external reference is extracted
+    /// from the new name, e.g. the rest of a split task.
+    pub fn into_task_named(self, name: String, pattern: &Regex) -> Self {
+        Self {
+            name,
+            break_category: None,
+            external_ref: None,
+            ..self
+        }
+        .fill_external_ref(pattern)
+    }
+
+    /// Merge a later task into the task, covering the union of their time.
+    ///
+    /// The merged task keeps the id and the name of the task, and has the tags of both tasks.
+    /// The note, the external reference and the repository of the later task are used only
+    /// if the task does not have them.
+    pub fn merge(&self, later: &Task) -> Self {
+        let end_time = match (self.end_time, later.end_time) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            _ => None,
+        };
+        let (repo, branch) = match self.repo {
+            Some(_) => (self.repo.clone(), self.branch.clone()),
+            None => (later.repo.clone(), later.branch.clone()),
+        };
+
+        Self {
+            end_time,
+            note: self.note.clone().or_else(|| later.note.clone()),
+            external_ref: self
+                .external_ref
+                .clone()
+                .or_else(|| later.external_ref.clone()),
+            repo,
+            branch,
+            ..self.clone()
+        }
+        .change_tags(&later.tags, &[])
+    }
+
+    /// Check whether the next task starts when the task ends, and both are working tasks or
+    /// breaks of the same category.
+    pub fn is_adjacent_to(&self, next: &Task) -> bool {
+        self.end_time == Some(next.start_time) && self.break_category == next.break_category
     }
 
     /// End the task.
@@ -78,6 +326,7 @@ impl Task {
     }
 
     /// Calculate the duration of the task.
+    #[allow(clippy::op_ref)]
     fn duration(&self) -> Option<Duration> {
         self.end_time.map(|t| &t - &self.start_time)
     }
@@ -105,6 +354,7 @@ impl TaskList {
     }
 
     /// Return the summary of tasks
+    #[allow(clippy::clone_on_copy)]
     pub fn summary(&self) -> Option<TaskSummary> {
         let tasks = self.tasks.clone();
 
@@ -128,19 +378,31 @@ impl TaskList {
         let end_last = end_times.clone().into_iter().max().unwrap();
         let duration_total = tasks
             .iter()
-            .filter(|(_, task)| !task.is_break_time && task.duration().is_some())
+            .filter(|(_, task)| task.is_working() && task.duration().is_some())
             .fold(Duration::seconds(0), |acc, (_, task)| {
                 acc + task.duration().unwrap()
             });
 
+        // sum durations by break categories, including ones counted as work
+        let mut category_durations_map: HashMap<String, Duration> = HashMap::new();
+        for (_, task) in &tasks {
+            if let Some(category) = &task.break_category {
+                let duration = task.duration().unwrap_or_else(|| Duration::seconds(0));
+                let duration_acc = category_durations_map
+                    .entry(category.name.clone())
+                    .or_insert_with(|| Duration::seconds(0));
+                *duration_acc = *duration_acc + duration;
+            }
+        }
+
         // separate tasks to working and break
         let mut tasks_working = Vec::new();
         let mut tasks_break = Vec::new();
         for (_, task) in tasks {
-            if task.is_break_time {
-                tasks_break.push(task);
-            } else {
+            if task.is_working() {
                 tasks_working.push(task);
+            } else {
+                tasks_break.push(task);
             }
         }
 
@@ -165,14 +427,139 @@ impl TaskList {
             durations_map.insert(name.to_string(), duration_acc + duration);
         }
 
+        // sum durations by tags (without break times)
+        let mut tag_durations_map: HashMap<String, Duration> = HashMap::new();
+        for task in &tasks_working {
+            let duration = task.duration().unwrap_or_else(|| Duration::seconds(0));
+            for tag in &task.tags {
+                let duration_acc = tag_durations_map
+                    .entry(tag.clone())
+                    .or_insert_with(|| Duration::seconds(0));
+                *duration_acc = *duration_acc + duration;
+            }
+        }
+
         Some(TaskSummary {
             start_time: start_first,
             end_time: end_last,
             duration_total,
             duration_by_taskname: durations_map,
+            duration_by_tag: tag_durations_map,
+            duration_by_break_category: category_durations_map,
             break_times: tasks_break,
         })
     }
+
+    /// Return a list of the tasks which have the tag.
+    pub fn filter_by_tag(&self, tag: &str) -> Self {
+        let tasks = self
+            .tasks
+            .iter()
+            .filter(|(_, task)| task.has_tag(tag))
+            .cloned()
+            .collect();
+
+        Self::new(tasks)
+    }
+
+    /// Check whether any task in the list has tags.
+    pub fn has_tags(&self) -> bool {
+        self.tasks.iter().any(|(_, task)| !task.tags.is_empty())
+    }
+
+    /// Return the task started last among the tasks which are not breaks.
+    pub fn last_non_break_task(&self) -> Option<&Task> {
+        self.tasks
+            .iter()
+            .map(|(_, task)| task)
+            .filter(|task| !task.is_break_time())
+            .max_by_key(|task| task.start_time)
+    }
+
+    /// Return the intervals which no task covers between the first start time and the last
+    /// end time. A running task covers the rest of the list.
+    pub fn gaps(&self) -> Vec<(TaskTime, TaskTime)> {
+        let mut tasks = self.tasks.iter().map(|(_, task)| task).collect::<Vec<_>>();
+        tasks.sort_by_key(|task| task.start_time);
+
+        let mut gaps = Vec::new();
+        let mut covered_until = match tasks.first() {
+            Some(task) => task.start_time,
+            None => return gaps,
+        };
+        for task in tasks {
+            if task.start_time > covered_until {
+                gaps.push((covered_until, task.start_time));
+            }
+            match task.end_time {
+                Some(end_time) => covered_until = covered_until.max(end_time),
+                None => break,
+            }
+        }
+
+        gaps
+    }
+
+    /// Return a copy of the list in which the running task is ended at `now`.
+    pub fn end_running_at(&self, running_id: u32, now: TaskTime) -> Self {
+        let tasks = self
+            .tasks
+            .iter()
+            .map(|(n, task)| {
+                if task.id == Some(running_id) && task.end_time.is_none() && now >= task.start_time
+                {
+                    let running = Task {
+                        end_time: Some(now),
+                        ..task.clone()
+                    };
+                    (*n, running)
+                } else {
+                    (*n, task.clone())
+                }
+            })
+            .collect();
+
+        Self::new(tasks)
+    }
+
+    /// Sum the durations of working (non-break) tasks by keys, tasks without a key are omitted.
+    pub fn working_duration_by<F>(&self, key: F) -> BTreeMap<String, Duration>
+    where
+        F: Fn(&Task) -> Option<String>,
+    {
+        let mut durations_map: BTreeMap<String, Duration> = BTreeMap::new();
+        for (_, task) in &self.tasks {
+            if !task.is_working() {
+                continue;
+            }
+            if let (Some(k), Some(duration)) = (key(task), task.duration()) {
+                let duration_acc = durations_map
+                    .entry(k)
+                    .or_insert_with(|| Duration::seconds(0));
+                *duration_acc = *duration_acc + duration;
+            }
+        }
+
+        durations_map
+    }
+
+    /// Sum the durations of working (non-break) tasks by working date.
+    pub fn working_duration_by_date(&self) -> HashMap<WorkDate, Duration> {
+        let mut durations_map: HashMap<WorkDate, Duration> = HashMap::new();
+        for (_, task) in &self.tasks {
+            if !task.is_working() {
+                continue;
+            }
+            if let Some(duration) = task.duration() {
+                let duration_acc = durations_map
+                    .entry(task.working_date())
+                    .or_insert_with(|| Duration::seconds(0));
+                *duration_acc = *duration_acc + duration;
+            }
+        }
+
+        durations_map
+    }
 }
 
 impl IntoIterator for TaskList {
@@ -196,9 +583,56 @@ pub struct TaskSummary {
     #[getset(get = "pub")]
     duration_by_taskname: HashMap<String, Duration>,
     #[getset(get = "pub")]
+    duration_by_tag: HashMap<String, Duration>,
+    #[getset(get = "pub")]
+    duration_by_break_category: HashMap<String, Duration>,
+    #[getset(get = "pub")]
     break_times: Vec<Task>,
 }
 
+impl TaskSummary {
+    /// Sum durations by task name into groups and items, `key` maps a task name to a pair of
+    /// its group and item (e.g. a project and the task name).
+    pub fn rollup<F>(&self, key: F) -> BTreeMap<String, BTreeMap<String, Duration>>
+    where
+        F: Fn(&str) -> (String, String),
+    {
+        let mut groups: BTreeMap<String, BTreeMap<String, Duration>> = BTreeMap::new();
+        for (name, duration) in &self.duration_by_taskname {
+            let (group, item) = key(name);
+            let duration_acc = groups
+                .entry(group)
+                .or_default()
+                .entry(item)
+                .or_insert_with(|| Duration::seconds(0));
+            *duration_acc = *duration_acc + *duration;
+        }
+
+        groups
+    }
+
+    /// Get durations by task name with their shares of the total duration, longest first.
+    pub fn task_shares(&self) -> Vec<(String, Duration, f64)> {
+        let total = self.duration_total.num_seconds();
+
+        let mut shares = self
+            .duration_by_taskname
+            .iter()
+            .map(|(name, duration)| {
+                let share = if total > 0 {
+                    duration.num_seconds() as f64 / total as f64
+                } else {
+                    0.0
+                };
+                (name.clone(), *duration, share)
+            })
+            .collect::<Vec<_>>();
+        shares.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        shares
+    }
+}
+
 /// A *date* for tasks which are considered belonging to the same day.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct WorkDate(NaiveDate);
@@ -210,20 +644,38 @@ impl WorkDate {
     }
 
     /// Create a `WorkDate` from string.
+    #[allow(clippy::needless_borrow)]
     pub fn parse_from_str(s: &str) -> Result<Self> {
         let (y, m, d) = parse_date(&s)?;
         let date = NaiveDate::from_ymd_opt(y, m, d).ok_or(anyhow!("invalid date"))?;
         Ok(WorkDate(date))
     }
+
+    /// Get the date shifted by the given number of days.
+    pub fn add_days(&self, days: i64) -> Self {
+        WorkDate(self.0 + Duration::days(days))
+    }
+
+    /// Get the day of the week, counting from Monday as `0`.
+    pub fn weekday_from_monday(&self) -> u32 {
+        self.0.weekday().num_days_from_monday()
+    }
+
+    /// Get the Monday of the week which the date belongs to.
+    pub fn week_start(&self) -> Self {
+        self.add_days(-(self.weekday_from_monday() as i64))
+    }
 }
 
 impl fmt::Display for WorkDate {
+    #[allow(clippy::to_string_in_format_args)]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0.format("%Y-%m-%d").to_string())
     }
 }
 
 impl From<TaskTime> for WorkDate {
+    #[allow(clippy::needless_borrow)]
     fn from(tasktime: TaskTime) -> Self {
         let today = tasktime.0.date();
         let start = &today.and_hms(5, 0, 0);
@@ -241,6 +693,7 @@ pub struct TaskTime(NaiveDateTime);
 
 impl TaskTime {
     ///
+    #[allow(clippy::empty_docs)]
     pub fn now() -> Self {
         let now = Local::now().naive_local();
         TaskTime::from(now)
@@ -265,9 +718,30 @@ impl TaskTime {
         let (hour, min) = parse_time_hm(s)?;
         Ok(TaskTime::from_hm(hour, min))
     }
+
+    /// Get the hour of the time.
+    pub fn hour(&self) -> u32 {
+        self.0.hour()
+    }
+
+    /// Truncate the minutes, leaving the time on the hour.
+    pub fn floor_hour(&self) -> Self {
+        TaskTime(self.0.with_minute(0).unwrap())
+    }
+
+    /// Round the time up to the next hour, unless it is already on the hour.
+    pub fn ceil_hour(&self) -> Self {
+        let floor = self.floor_hour();
+        if floor == *self {
+            floor
+        } else {
+            floor + Duration::hours(1)
+        }
+    }
 }
 
 impl fmt::Display for TaskTime {
+    #[allow(clippy::to_string_in_format_args)]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0.format("%Y-%m-%dT%H:%M:%S").to_string())
     }
@@ -279,6 +753,7 @@ impl From<NaiveDateTime> for TaskTime {
     }
 }
 
+#[allow(clippy::needless_lifetimes)]
 impl<'a, 'b> ops::Sub<&'a TaskTime> for &'b TaskTime {
     type Output = Duration;
 
@@ -295,6 +770,14 @@ impl ops::Sub<TaskTime> for TaskTime {
     }
 }
 
+impl ops::Add<Duration> for TaskTime {
+    type Output = TaskTime;
+
+    fn add(self, other: Duration) -> TaskTime {
+        TaskTime(self.0 + other)
+    }
+}
+
 impl TimeDisplay for TaskTime {
     fn to_string_hhmm(&self) -> String {
         self.0.format("%H:%M").to_string()
@@ -312,9 +795,10 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::redundant_field_names)]
     fn test_task_start() {
         let start_time = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(11, 6, 0));
-        let task = Task::start(String::from("task a"), start_time, false);
+        let task = Task::start(String::from("task a"), start_time, None);
         assert_eq!(
             task,
             Task {
@@ -322,12 +806,18 @@ mod tests {
                 name: String::from("task a"),
                 start_time: start_time,
                 end_time: None,
-                is_break_time: false
+                break_category: None,
+                tags: vec![],
+                note: None,
+                external_ref: None,
+                repo: None,
+                branch: None
             },
         );
     }
 
     #[test]
+    #[allow(clippy::redundant_field_names)]
     fn test_task_end() {
         let start_time = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(11, 6, 0));
         let task = Task {
@@ -335,7 +825,12 @@ mod tests {
             name: String::from("task a"),
             start_time: start_time,
             end_time: None,
-            is_break_time: false,
+            break_category: None,
+            tags: vec![],
+            note: None,
+            external_ref: None,
+            repo: None,
+            branch: None,
         };
 
         let end_time1 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(11, 30, 0));
@@ -346,7 +841,12 @@ mod tests {
                 name: String::from("task a"),
                 start_time: start_time,
                 end_time: Some(end_time1),
-                is_break_time: false
+                break_category: None,
+                tags: vec![],
+                note: None,
+                external_ref: None,
+                repo: None,
+                branch: None
             },
         );
 
@@ -355,6 +855,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::op_ref)]
     fn test_tasktime_duration() {
         let t1 = TaskTime(NaiveDate::from_ymd(2015, 9, 18).and_hms(23, 56, 0));
         let t2 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(1, 10, 0));
@@ -377,13 +878,13 @@ mod tests {
         let s3 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 40, 0));
         let e3 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 55, 0));
 
-        let task1 = Task::start(String::from("task a"), s1, false)
+        let task1 = Task::start(String::from("task a"), s1, None)
             .end(e1)
             .unwrap();
-        let task2 = Task::start(String::from("task b"), s2, false)
+        let task2 = Task::start(String::from("task b"), s2, None)
             .end(e2)
             .unwrap();
-        let task3 = Task::start(String::from("task a"), s3, false)
+        let task3 = Task::start(String::from("task a"), s3, None)
             .end(e3)
             .unwrap();
 
@@ -403,6 +904,8 @@ mod tests {
                 end_time: e3,
                 duration_total: Duration::minutes(55),
                 duration_by_taskname: duration_map,
+                duration_by_tag: HashMap::new(),
+                duration_by_break_category: HashMap::new(),
                 break_times: vec![]
             })
         );
@@ -417,15 +920,23 @@ mod tests {
         let s3 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 40, 0));
         let e3 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 55, 0));
 
-        let task1 = Task::start(String::from("task a"), s1, true)
-            .end(e1)
-            .unwrap();
-        let task2 = Task::start(String::from("task b"), s2, false)
+        let task1 = Task::start(
+            String::from("task a"),
+            s1,
+            Some(BreakCategory::new(String::from("break time"), false)),
+        )
+        .end(e1)
+        .unwrap();
+        let task2 = Task::start(String::from("task b"), s2, None)
             .end(e2)
             .unwrap();
-        let task3 = Task::start(String::from("task c"), s3, true)
-            .end(e3)
-            .unwrap();
+        let task3 = Task::start(
+            String::from("task c"),
+            s3,
+            Some(BreakCategory::new(String::from("break time"), false)),
+        )
+        .end(e3)
+        .unwrap();
 
         let tasklist = TaskList::new(vec![]);
         assert!(tasklist.summary().is_none());
@@ -434,6 +945,8 @@ mod tests {
 
         let mut duration_map = HashMap::new();
         duration_map.insert(String::from("task b"), Duration::minutes(10));
+        let mut category_map = HashMap::new();
+        category_map.insert(String::from("break time"), Duration::minutes(45));
 
         assert_eq!(
             tasklist.summary(),
@@ -442,6 +955,8 @@ mod tests {
                 end_time: e3,
                 duration_total: Duration::minutes(10),
                 duration_by_taskname: duration_map,
+                duration_by_tag: HashMap::new(),
+                duration_by_break_category: category_map,
                 break_times: vec![task1, task3]
             })
         );
@@ -508,10 +1023,562 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::op_ref)]
     fn test_tasktime_subtractions() {
         let t1 = TaskTime(NaiveDate::from_ymd(2021, 1, 1).and_hms(12, 30, 0));
         let t2 = TaskTime(NaiveDate::from_ymd(2021, 1, 1).and_hms(12, 45, 0));
         assert_eq!(&t2 - &t1, Duration::minutes(15));
         assert_eq!(t2 - t1, Duration::minutes(15));
     }
+
+    #[test]
+    fn test_tasktime_round_to_hour() {
+        let t1 = TaskTime(NaiveDate::from_ymd(2021, 1, 1).and_hms(12, 30, 0));
+        let t2 = TaskTime(NaiveDate::from_ymd(2021, 1, 1).and_hms(23, 10, 0));
+        let t3 = TaskTime(NaiveDate::from_ymd(2021, 1, 1).and_hms(14, 0, 0));
+        assert_eq!(
+            t1.floor_hour(),
+            TaskTime(NaiveDate::from_ymd(2021, 1, 1).and_hms(12, 0, 0))
+        );
+        assert_eq!(
+            t1.ceil_hour(),
+            TaskTime(NaiveDate::from_ymd(2021, 1, 1).and_hms(13, 0, 0))
+        );
+        assert_eq!(
+            t2.ceil_hour(),
+            TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(0, 0, 0))
+        );
+        assert_eq!(t3.floor_hour(), t3);
+        assert_eq!(t3.ceil_hour(), t3);
+    }
+
+    #[test]
+    fn test_tasklist_working_duration_by_date() {
+        let s1 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 0, 0));
+        let e1 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 30, 0));
+        let s2 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 30, 0));
+        let e2 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 40, 0));
+        let s3 = TaskTime(NaiveDate::from_ymd(2015, 9, 20).and_hms(10, 40, 0));
+        let e3 = TaskTime(NaiveDate::from_ymd(2015, 9, 20).and_hms(10, 55, 0));
+        let s4 = TaskTime(NaiveDate::from_ymd(2015, 9, 20).and_hms(11, 0, 0));
+
+        let task1 = Task::start(String::from("task a"), s1, None)
+            .end(e1)
+            .unwrap();
+        let task2 = Task::start(
+            String::from("task b"),
+            s2,
+            Some(BreakCategory::new(String::from("break time"), false)),
+        )
+        .end(e2)
+        .unwrap();
+        let task3 = Task::start(String::from("task a"), s3, None)
+            .end(e3)
+            .unwrap();
+        let task4 = Task::start(String::from("task b"), s4, None);
+
+        let tasklist = TaskList::new(vec![(1, task1), (2, task2), (1, task3), (2, task4)]);
+
+        let mut duration_map = HashMap::new();
+        duration_map.insert(
+            WorkDate::parse_from_str("2015-09-19").unwrap(),
+            Duration::minutes(30),
+        );
+        duration_map.insert(
+            WorkDate::parse_from_str("2015-09-20").unwrap(),
+            Duration::minutes(15),
+        );
+
+        assert_eq!(tasklist.working_duration_by_date(), duration_map);
+    }
+
+    #[test]
+    fn test_workdate_week_start() {
+        // 2021-05-12 is Wednesday
+        let date = WorkDate::parse_from_str("2021-05-12").unwrap();
+        assert_eq!(date.weekday_from_monday(), 2);
+        assert_eq!(
+            date.week_start(),
+            WorkDate::parse_from_str("2021-05-10").unwrap()
+        );
+        assert_eq!(
+            date.add_days(7),
+            WorkDate::parse_from_str("2021-05-19").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_tasksummary_task_shares() {
+        let s1 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 0, 0));
+        let e1 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 10, 0));
+        let s2 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 10, 0));
+        let e2 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 40, 0));
+        let s3 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 40, 0));
+        let e3 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 50, 0));
+
+        let task1 = Task::start(String::from("task b"), s1, None)
+            .end(e1)
+            .unwrap();
+        let task2 = Task::start(String::from("task c"), s2, None)
+            .end(e2)
+            .unwrap();
+        let task3 = Task::start(String::from("task a"), s3, None)
+            .end(e3)
+            .unwrap();
+
+        let tasklist = TaskList::new(vec![(1, task1), (2, task2), (3, task3)]);
+
+        assert_eq!(
+            tasklist.summary().unwrap().task_shares(),
+            vec![
+                (String::from("task c"), Duration::minutes(30), 0.6),
+                (String::from("task a"), Duration::minutes(10), 0.2),
+                (String::from("task b"), Duration::minutes(10), 0.2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tasklist_end_running_at() {
+        let s1 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 0, 0));
+        let e1 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 30, 0));
+        let s2 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 30, 0));
+        let now = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(11, 0, 0));
+
+        let task1 = Task::new(Some(1), String::from("task a"), s1, Some(e1), None);
+        let task2 = Task::new(Some(2), String::from("task b"), s2, None, None);
+        let tasklist = TaskList::new(vec![(1, task1.clone()), (2, task2.clone())]);
+
+        let task2_running = Task::new(Some(2), String::from("task b"), s2, Some(now), None);
+        let tasklist_running = tasklist.end_running_at(2, now);
+        assert_eq!(
+            tasklist_running,
+            TaskList::new(vec![(1, task1.clone()), (2, task2_running)])
+        );
+        assert_eq!(
+            tasklist_running.summary().unwrap().duration_total(),
+            &Duration::minutes(60)
+        );
+
+        // not running or not started yet
+        assert_eq!(tasklist.end_running_at(3, now), tasklist);
+        assert_eq!(tasklist.end_running_at(2, s1), tasklist);
+    }
+
+    #[test]
+    fn test_tasksummary_rollup() {
+        let s1 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 0, 0));
+        let e1 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 10, 0));
+        let s2 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 10, 0));
+        let e2 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 40, 0));
+        let s3 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 40, 0));
+        let e3 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 50, 0));
+
+        let task1 = Task::start(String::from("task a"), s1, None)
+            .end(e1)
+            .unwrap();
+        let task2 = Task::start(String::from("task b"), s2, None)
+            .end(e2)
+            .unwrap();
+        let task3 = Task::start(String::from("task c"), s3, None)
+            .end(e3)
+            .unwrap();
+        let summary = TaskList::new(vec![(1, task1), (2, task2), (3, task3)])
+            .summary()
+            .unwrap();
+
+        let rollup = summary.rollup(|name| {
+            let group = if name == "task c" { "y" } else { "x" };
+            (String::from(group), String::from(name))
+        });
+
+        let mut group_x = BTreeMap::new();
+        group_x.insert(String::from("task a"), Duration::minutes(10));
+        group_x.insert(String::from("task b"), Duration::minutes(30));
+        let mut group_y = BTreeMap::new();
+        group_y.insert(String::from("task c"), Duration::minutes(10));
+        let mut expected = BTreeMap::new();
+        expected.insert(String::from("x"), group_x);
+        expected.insert(String::from("y"), group_y);
+
+        assert_eq!(rollup, expected);
+    }
+
+    #[test]
+    fn test_task_tags() {
+        let start_time = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(11, 6, 0));
+        let task = Task::start(String::from("task a"), start_time, None).with_tags(vec![
+            String::from("review"),
+            String::from("meeting"),
+            String::from("review"),
+        ]);
+        assert_eq!(
+            task.tags(),
+            &vec![String::from("meeting"), String::from("review")]
+        );
+        assert!(task.has_tag("meeting"));
+        assert!(!task.has_tag("oncall"));
+
+        let task = task.change_tags(&[String::from("oncall")], &[String::from("meeting")]);
+        assert_eq!(
+            task.tags(),
+            &vec![String::from("oncall"), String::from("review")]
+        );
+    }
+
+    #[test]
+    fn test_tasklist_summary_by_tag() {
+        let s1 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 0, 0));
+        let e1 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 30, 0));
+        let s2 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 30, 0));
+        let e2 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 40, 0));
+        let s3 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 40, 0));
+        let e3 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 55, 0));
+
+        let task1 = Task::start(String::from("task a"), s1, None)
+            .with_tags(vec![String::from("meeting"), String::from("review")])
+            .end(e1)
+            .unwrap();
+        let task2 = Task::start(
+            String::from("task b"),
+            s2,
+            Some(BreakCategory::new(String::from("break time"), false)),
+        )
+        .with_tags(vec![String::from("meeting")])
+        .end(e2)
+        .unwrap();
+        let task3 = Task::start(String::from("task b"), s3, None)
+            .with_tags(vec![String::from("meeting")])
+            .end(e3)
+            .unwrap();
+
+        let tasklist = TaskList::new(vec![(1, task1.clone()), (2, task2), (3, task3.clone())]);
+
+        let mut duration_map = HashMap::new();
+        duration_map.insert(String::from("meeting"), Duration::minutes(45));
+        duration_map.insert(String::from("review"), Duration::minutes(30));
+        assert_eq!(tasklist.summary().unwrap().duration_by_tag(), &duration_map);
+
+        assert_eq!(
+            tasklist.filter_by_tag("review"),
+            TaskList::new(vec![(1, task1)])
+        );
+        assert_eq!(tasklist.filter_by_tag("oncall"), TaskList::new(vec![]));
+    }
+
+    #[test]
+    fn test_tasklist_summary_by_break_category() {
+        let s1 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 0, 0));
+        let e1 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 30, 0));
+        let s2 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 30, 0));
+        let e2 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(11, 30, 0));
+        let s3 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(11, 30, 0));
+        let e3 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(12, 0, 0));
+
+        let lunch = BreakCategory::new(String::from("lunch"), false);
+        let commute = BreakCategory::new(String::from("commute"), true);
+        let task1 = Task::start(String::from("commute"), s1, Some(commute))
+            .end(e1)
+            .unwrap();
+        let task2 = Task::start(String::from("task a"), s2, None)
+            .end(e2)
+            .unwrap();
+        let task3 = Task::start(String::from("lunch"), s3, Some(lunch))
+            .end(e3)
+            .unwrap();
+        assert!(task1.is_break_time() && task1.is_working());
+        assert!(!task2.is_break_time() && task2.is_working());
+        assert!(task3.is_break_time() && !task3.is_working());
+
+        let tasklist = TaskList::new(vec![(1, task1), (2, task2), (3, task3.clone())]);
+        let summary = tasklist.summary().unwrap();
+
+        let mut category_map = HashMap::new();
+        category_map.insert(String::from("commute"), Duration::minutes(30));
+        category_map.insert(String::from("lunch"), Duration::minutes(30));
+        assert_eq!(summary.duration_by_break_category(), &category_map);
+
+        let mut duration_map = HashMap::new();
+        duration_map.insert(String::from("commute"), Duration::minutes(30));
+        duration_map.insert(String::from("task a"), Duration::minutes(60));
+        assert_eq!(summary.duration_by_taskname(), &duration_map);
+        assert_eq!(summary.break_times(), &vec![task3]);
+    }
+
+    #[test]
+    fn test_tasklist_last_non_break_task() {
+        let s1 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(10, 0, 0));
+        let s2 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(11, 0, 0));
+        let s3 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(12, 0, 0));
+
+        let task1 = Task::start(String::from("task a"), s1, None);
+        let task2 = Task::start(String::from("task b"), s2, None);
+        let task3 = Task::start(
+            String::from("lunch"),
+            s3,
+            Some(BreakCategory::new(String::from("lunch"), false)),
+        );
+
+        let tasklist = TaskList::new(vec![(1, task1), (2, task2.clone()), (3, task3.clone())]);
+        assert_eq!(tasklist.last_non_break_task(), Some(&task2));
+
+        let tasklist = TaskList::new(vec![(1, task3)]);
+        assert_eq!(tasklist.last_non_break_task(), None);
+    }
+
+    #[test]
+    fn test_tasklist_gaps() {
+        let t = |h, m| TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(h, m, 0));
+
+        let task1 = Task::new(
+            Some(1),
+            String::from("task a"),
+            t(9, 0),
+            Some(t(10, 0)),
+            None,
+        );
+        let task2 = Task::new(
+            Some(2),
+            String::from("task b"),
+            t(9, 30),
+            Some(t(10, 30)),
+            None,
+        );
+        let task3 = Task::new(
+            Some(3),
+            String::from("task a"),
+            t(11, 0),
+            Some(t(12, 0)),
+            None,
+        );
+        let task4 = Task::new(Some(4), String::from("task b"), t(12, 15), None, None);
+        let task5 = Task::new(
+            Some(5),
+            String::from("task a"),
+            t(13, 0),
+            Some(t(14, 0)),
+            None,
+        );
+
+        assert_eq!(TaskList::new(vec![]).gaps(), vec![]);
+
+        let tasklist = TaskList::new(vec![(1, task1), (2, task2), (3, task3)]);
+        assert_eq!(tasklist.gaps(), vec![(t(10, 30), t(11, 0))]);
+
+        let mut tasks = tasklist.into_iter().collect::<Vec<_>>();
+        tasks.push((4, task4));
+        tasks.push((5, task5));
+        assert_eq!(
+            TaskList::new(tasks).gaps(),
+            vec![(t(10, 30), t(11, 0)), (t(12, 0), t(12, 15))]
+        );
+    }
+
+    #[test]
+    fn test_task_restart() {
+        let s1 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(11, 6, 0));
+        let e1 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(11, 30, 0));
+        let s2 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(12, 0, 0));
+
+        let task = Task::new(Some(1), String::from("task a"), s1, Some(e1), None)
+            .with_tags(vec![String::from("review")])
+            .with_note(Some(String::from("login bug")));
+        let restarted = task.restart(s2);
+
+        assert_eq!(
+            restarted,
+            Task::start(String::from("task a"), s2, None)
+                .with_tags(vec![String::from("review")])
+                .with_note(Some(String::from("login bug")))
+        );
+    }
+
+    #[test]
+    fn test_task_is_reopened_at() {
+        let s1 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(9, 0, 0));
+        let e1 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(10, 0, 0));
+        let t = |m| TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(10, m, 0));
+        let max_gap = Duration::minutes(15);
+
+        let task = Task::new(Some(1), String::from("task a"), s1, Some(e1), None);
+        assert!(task.is_reopened_at(t(0), max_gap).unwrap());
+        assert!(task.is_reopened_at(t(14), max_gap).unwrap());
+        assert!(!task.is_reopened_at(t(15), max_gap).unwrap());
+        assert!(!task.is_reopened_at(t(16), max_gap).unwrap());
+
+        // a time before the end is rejected
+        let before_end = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(9, 59, 0));
+        assert!(task.is_reopened_at(before_end, max_gap).is_err());
+
+        // a running task can not be continued
+        let running = Task::new(Some(1), String::from("task a"), s1, None, None);
+        assert!(running.is_reopened_at(t(0), max_gap).is_err());
+    }
+
+    #[test]
+    fn test_task_split_at() {
+        let s1 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(9, 0, 0));
+        let t1 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(10, 30, 0));
+        let e1 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(12, 0, 0));
+
+        let task = Task::new(Some(1), String::from("task a"), s1, Some(e1), None)
+            .with_tags(vec![String::from("review")]);
+        let (first, second) = task.split_at(t1).unwrap();
+        assert_eq!(
+            first,
+            Task::new(Some(1), String::from("task a"), s1, Some(t1), None)
+                .with_tags(vec![String::from("review")])
+        );
+        assert_eq!(
+            second,
+            Task::new(None, String::from("task a"), t1, Some(e1), None)
+                .with_tags(vec![String::from("review")])
+        );
+
+        assert!(task.split_at(s1).is_err());
+        assert!(task.split_at(e1).is_err());
+
+        // a running task can be split after its start
+        let running = Task::new(Some(1), String::from("task a"), s1, None, None);
+        let (first, second) = running.split_at(e1).unwrap();
+        assert_eq!(first.end_time(), &Some(e1));
+        assert_eq!(second.end_time(), &None);
+    }
+
+    #[test]
+    fn test_task_into_task_named() {
+        let s1 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(12, 0, 0));
+        let t1 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(12, 30, 0));
+        let e1 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(13, 0, 0));
+        let pattern = Regex::new(r"\b[A-Z][A-Z0-9]+-[0-9]+\b").unwrap();
+
+        let lunch = Some(BreakCategory::new(String::from("lunch"), false));
+        let task = Task::new(Some(1), String::from("lunch"), s1, Some(e1), lunch.clone());
+        let (first, second) = task.split_at(t1).unwrap();
+        let second = second.into_task_named(String::from("fix PROJ-7"), &pattern);
+        assert_eq!(first.break_category(), &lunch);
+        assert_eq!(second.name(), "fix PROJ-7");
+        assert!(!second.is_break_time());
+        assert_eq!(second.external_ref(), &Some(String::from("PROJ-7")));
+    }
+
+    #[test]
+    fn test_task_merge() {
+        let s1 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(9, 0, 0));
+        let e1 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(10, 0, 0));
+        let s2 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(10, 0, 0));
+        let e2 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(11, 0, 0));
+        let s3 = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(11, 30, 0));
+        let lunch = BreakCategory::new(String::from("lunch"), false);
+
+        let task1 = Task::new(Some(1), String::from("task a"), s1, Some(e1), None)
+            .with_tags(vec![String::from("review")]);
+        let task2 = Task::new(Some(2), String::from("task b"), s2, Some(e2), None)
+            .with_tags(vec![String::from("meeting")])
+            .with_note(Some(String::from("login bug")));
+        let task3 = Task::new(Some(3), String::from("task a"), s3, None, None);
+        let task4 = Task::new(Some(4), String::from("lunch"), s2, Some(e2), Some(lunch));
+
+        assert_eq!(
+            task1.merge(&task2),
+            Task::new(Some(1), String::from("task a"), s1, Some(e2), None)
+                .with_tags(vec![String::from("meeting"), String::from("review")])
+                .with_note(Some(String::from("login bug")))
+        );
+        assert_eq!(task2.merge(&task3).end_time(), &None);
+
+        assert!(task1.is_adjacent_to(&task2));
+        assert!(!task2.is_adjacent_to(&task3));
+        assert!(!task1.is_adjacent_to(&task4));
+    }
+
+    #[test]
+    fn test_task_fill_external_ref() {
+        let start_time = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(11, 6, 0));
+        let pattern = Regex::new(r"\b[A-Z][A-Z0-9]+-[0-9]+\b").unwrap();
+
+        let task = Task::start(String::from("PROJ-12 review"), start_time, None)
+            .with_note(Some(String::from("see PROJ-34")))
+            .fill_external_ref(&pattern);
+        assert_eq!(task.external_ref(), &Some(String::from("PROJ-12")));
+
+        let task = Task::start(String::from("review"), start_time, None)
+            .with_note(Some(String::from("see PROJ-34")))
+            .fill_external_ref(&pattern);
+        assert_eq!(task.external_ref(), &Some(String::from("PROJ-34")));
+
+        // an explicit reference is kept
+        let task = Task::start(String::from("PROJ-12 review"), start_time, None)
+            .with_external_ref(Some(String::from("OPS-1")))
+            .fill_external_ref(&pattern);
+        assert_eq!(task.external_ref(), &Some(String::from("OPS-1")));
+
+        // the first capture group is used if any
+        let pattern = Regex::new(r"gh-([0-9]+)").unwrap();
+        let task =
+            Task::start(String::from("fix gh-42"), start_time, None).fill_external_ref(&pattern);
+        assert_eq!(task.external_ref(), &Some(String::from("42")));
+
+        let task =
+            Task::start(String::from("meeting"), start_time, None).fill_external_ref(&pattern);
+        assert_eq!(task.external_ref(), &None);
+    }
+
+    #[test]
+    fn test_task_rename_refills_external_ref() {
+        let start_time = TaskTime(NaiveDate::from_ymd(2021, 1, 2).and_hms(11, 6, 0));
+        let pattern = Regex::new(r"\b[A-Z][A-Z0-9]+-[0-9]+\b").unwrap();
+
+        // an extracted reference follows the name
+        let task = Task::start(String::from("PROJ-1 fix"), start_time, None)
+            .fill_external_ref(&pattern)
+            .rename(String::from("PROJ-2 fix"), &pattern);
+        assert_eq!(task.external_ref(), &Some(String::from("PROJ-2")));
+
+        let task = task.rename(String::from("fix"), &pattern);
+        assert_eq!(task.external_ref(), &None);
+
+        // an extracted reference follows the note
+        let task = Task::start(String::from("review"), start_time, None)
+            .with_note(Some(String::from("see PROJ-3")))
+            .fill_external_ref(&pattern)
+            .change_note(Some(String::from("see PROJ-4")), &pattern);
+        assert_eq!(task.external_ref(), &Some(String::from("PROJ-4")));
+
+        // an explicit reference is kept
+        let task = Task::start(String::from("PROJ-1 fix"), start_time, None)
+            .with_external_ref(Some(String::from("OPS-1")))
+            .rename(String::from("PROJ-2 fix"), &pattern)
+            .change_note(Some(String::from("see PROJ-4")), &pattern);
+        assert_eq!(task.external_ref(), &Some(String::from("OPS-1")));
+    }
+
+    #[test]
+    fn test_tasklist_working_duration_by() {
+        let s1 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 0, 0));
+        let e1 = TaskTime(NaiveDate::from_ymd(2015, 9, 19).and_hms(10, 30, 0));
+        let s2 = TaskTime(NaiveDate::from_ymd(2015, 9, 20).and_hms(10, 0, 0));
+        let e2 = TaskTime(NaiveDate::from_ymd(2015, 9, 20).and_hms(10, 20, 0));
+        let s3 = TaskTime(NaiveDate::from_ymd(2015, 9, 20).and_hms(10, 20, 0));
+
+        let task1 = Task::start(String::from("task a"), s1, None)
+            .with_external_ref(Some(String::from("PROJ-1")))
+            .end(e1)
+            .unwrap();
+        let task2 = Task::start(String::from("task b"), s2, None)
+            .with_external_ref(Some(String::from("PROJ-1")))
+            .end(e2)
+            .unwrap();
+        let task3 = Task::start(String::from("task c"), s3, None)
+            .with_external_ref(Some(String::from("PROJ-2")));
+
+        let tasklist = TaskList::new(vec![(1, task1), (1, task2), (2, task3)]);
+
+        let mut duration_map = BTreeMap::new();
+        duration_map.insert(String::from("PROJ-1"), Duration::minutes(50));
+        assert_eq!(
+            tasklist.working_duration_by(|task| task.external_ref().clone()),
+            duration_map
+        );
+    }
 }